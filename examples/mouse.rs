@@ -1,13 +1,16 @@
 use std::{fmt::Write, time::Duration};
 
-use tuinix::{Terminal, TerminalColor, TerminalEvent, TerminalFrame, TerminalInput, TerminalStyle};
+use tuinix::{
+    MouseTracking, Terminal, TerminalColor, TerminalEvent, TerminalFrame, TerminalInput,
+    TerminalStyle,
+};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize terminal
     let mut terminal = Terminal::new()?;
 
-    // Enable mouse input reporting
-    terminal.enable_mouse_input()?;
+    // Enable mouse input reporting, including motion without buttons held
+    terminal.enable_mouse_tracking(MouseTracking::AnyMotion)?;
 
     // Create a frame with the terminal's dimensions
     let mut frame: TerminalFrame = TerminalFrame::new(terminal.size());
@@ -154,9 +157,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             tuinix::MouseEvent::MiddleRelease => {
                                 writeln!(frame, "  → Middle button released")?
                             }
-                            tuinix::MouseEvent::Drag => writeln!(frame, "  → Mouse dragged")?,
+                            tuinix::MouseEvent::Drag(button) => {
+                                writeln!(frame, "  → Mouse dragged with {button:?} button")?
+                            }
+                            tuinix::MouseEvent::Move => writeln!(frame, "  → Mouse moved")?,
                             tuinix::MouseEvent::ScrollUp => writeln!(frame, "  → Scrolled up")?,
                             tuinix::MouseEvent::ScrollDown => writeln!(frame, "  → Scrolled down")?,
+                            tuinix::MouseEvent::ScrollLeft => writeln!(frame, "  → Scrolled left")?,
+                            tuinix::MouseEvent::ScrollRight => {
+                                writeln!(frame, "  → Scrolled right")?
+                            }
+                            tuinix::MouseEvent::ExtraPress(n) => {
+                                writeln!(frame, "  → Button {n} pressed")?
+                            }
+                            tuinix::MouseEvent::ExtraRelease(n) => {
+                                writeln!(frame, "  → Button {n} released")?
+                            }
                         }
 
                         terminal.draw(frame)?;
@@ -0,0 +1,74 @@
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes data as standard Base64 (RFC 4648) with padding, as used by OSC 52.
+pub fn encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - i * 8));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - i * 6)) as usize & 0x3f] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes a Base64 string, returning `None` if it is malformed.
+pub fn decode(s: &str) -> Option<Vec<u8>> {
+    let s = s.trim_end_matches('=').as_bytes();
+    if s.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut decoded = Vec::with_capacity(s.len() * 3 / 4);
+    for chunk in s.chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let v = ALPHABET.iter().position(|&a| a == c)? as u32;
+            n |= v << (18 - i * 6);
+        }
+        for i in 0..chunk.len() - 1 {
+            decoded.push((n >> (16 - i * 8)) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_and_decode() {
+        let cases: [(&[u8], &str); 7] = [
+            (b"", ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"fooba", "Zm9vYmE="),
+            (b"foobar", "Zm9vYmFy"),
+        ];
+        for (data, text) in cases {
+            assert_eq!(encode(data), text);
+            assert_eq!(decode(text).as_deref(), Some(data));
+        }
+
+        let data = "こんにちは, world! \u{1F600}".as_bytes();
+        assert_eq!(decode(&encode(data)).as_deref(), Some(data));
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(decode("Z"), None);
+        assert_eq!(decode("Zm9vY"), None);
+        assert_eq!(decode("Zm9!"), None);
+        assert_eq!(decode("Zm9vYm!="), None);
+    }
+}
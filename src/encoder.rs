@@ -0,0 +1,506 @@
+use crate::{KeyCode, KeyInput, MouseButton, MouseEvent, MouseInput, TerminalInput};
+
+/// Encoding of mouse inputs produced by [`InputEncoder`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MouseEncoding {
+    /// X10 encoding (`CSI M Cb Cx Cy`), where each value is sent as a single byte.
+    ///
+    /// Positions beyond column or row 223 cannot be encoded, and releases do not
+    /// tell which button was released.
+    X10,
+
+    /// SGR encoding (mode 1006, `CSI < Cb ; Cx ; Cy M` or `m` for releases).
+    #[default]
+    Sgr,
+
+    /// SGR-pixels encoding (mode 1016), which is the SGR encoding with
+    /// [`MouseInput::pixel_position`] instead of the cell position.
+    SgrPixels,
+}
+
+/// Encoder of [`TerminalInput`]s into the byte sequences a terminal sends for them.
+///
+/// This is the inverse of the input decoding done by [`Terminal::read_input()`](crate::Terminal::read_input)
+/// and [`InputParser`](crate::InputParser), so that inputs can be forwarded to a child program
+/// running in a pty. Keys are encoded as xterm does by default, with modifiers of special keys
+/// in the `CSI 1 ; <modifier> <final>` form and Alt as an `ESC` prefix of characters.
+///
+/// Inputs that the terminal cannot send (e.g., Ctrl with a digit, or a mouse input without
+/// a pixel position in [`MouseEncoding::SgrPixels`]) are not encoded.
+///
+/// # Examples
+///
+/// ```
+/// use tuinix::{InputEncoder, KeyCode, KeyInput, TerminalInput};
+///
+/// let up = TerminalInput::Key(KeyInput { ctrl: false, alt: false, code: KeyCode::Up });
+/// assert_eq!(InputEncoder::new().encode(up), Some(b"\x1b[A".to_vec()));
+///
+/// // The child program enabled the application cursor keys mode (DECCKM)
+/// let encoder = InputEncoder::new().application_cursor();
+/// assert_eq!(encoder.encode(up), Some(b"\x1bOA".to_vec()));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputEncoder {
+    /// Whether unmodified arrow, Home and End keys are encoded as SS3 sequences (e.g., `ESC O A`)
+    /// as in the application cursor keys mode (DECCKM), instead of CSI sequences (e.g., `ESC [ A`).
+    pub application_cursor: bool,
+
+    /// Whether unmodified keypad keys ([`KeyCode::Keypad`] and [`KeyCode::KeypadEnter`]) are
+    /// encoded as SS3 sequences (e.g., `ESC O k` for `+`) as in the application keypad mode
+    /// (DECKPAM), instead of as the corresponding main keys.
+    pub application_keypad: bool,
+
+    /// Whether characters and the Enter, Tab, Backspace and Escape keys with modifiers are
+    /// encoded as `CSI 27 ; <modifier> ; <code> ~` as with xterm's `modifyOtherKeys` level 2,
+    /// which allows combinations like Ctrl+1 and Ctrl+Enter.
+    pub modify_other_keys: bool,
+
+    /// Encoding of mouse inputs.
+    pub mouse_encoding: MouseEncoding,
+}
+
+impl InputEncoder {
+    /// Makes a new [`InputEncoder`] instance with the default settings.
+    pub const fn new() -> Self {
+        Self {
+            application_cursor: false,
+            application_keypad: false,
+            modify_other_keys: false,
+            mouse_encoding: MouseEncoding::Sgr,
+        }
+    }
+
+    /// Enables the application cursor keys mode.
+    pub const fn application_cursor(mut self) -> Self {
+        self.application_cursor = true;
+        self
+    }
+
+    /// Enables the application keypad mode.
+    pub const fn application_keypad(mut self) -> Self {
+        self.application_keypad = true;
+        self
+    }
+
+    /// Enables xterm's `modifyOtherKeys` level 2.
+    pub const fn modify_other_keys(mut self) -> Self {
+        self.modify_other_keys = true;
+        self
+    }
+
+    /// Sets the encoding of mouse inputs.
+    pub const fn mouse_encoding(mut self, encoding: MouseEncoding) -> Self {
+        self.mouse_encoding = encoding;
+        self
+    }
+
+    /// Encodes an input, returning `None` if it cannot be encoded.
+    pub fn encode(&self, input: TerminalInput) -> Option<Vec<u8>> {
+        match input {
+            TerminalInput::Key(key) => self.encode_key(key),
+            TerminalInput::Mouse(mouse) => self.encode_mouse(mouse),
+        }
+    }
+
+    /// Encodes a key input, returning `None` if it cannot be encoded.
+    pub fn encode_key(&self, key: KeyInput) -> Option<Vec<u8>> {
+        let modifier = 1 + if key.alt { 2 } else { 0 } + if key.ctrl { 4 } else { 0 };
+        let cursor = |final_byte: char| {
+            if modifier != 1 {
+                format!("\x1b[1;{modifier}{final_byte}")
+            } else if self.application_cursor {
+                format!("\x1bO{final_byte}")
+            } else {
+                format!("\x1b[{final_byte}")
+            }
+        };
+        let tilde = |n: u8| {
+            if modifier != 1 {
+                format!("\x1b[{n};{modifier}~")
+            } else {
+                format!("\x1b[{n}~")
+            }
+        };
+
+        let bytes = match key.code {
+            KeyCode::Keypad(c) if self.application_keypad && modifier == 1 => {
+                format!("\x1bO{}", keypad_final_byte(c)? as char)
+            }
+            KeyCode::KeypadEnter if self.application_keypad && modifier == 1 => "\x1bOM".to_owned(),
+            KeyCode::Keypad(c) => {
+                let code = KeyCode::Char(c);
+                return self.encode_key(KeyInput { code, ..key });
+            }
+            KeyCode::KeypadEnter => {
+                let code = KeyCode::Enter;
+                return self.encode_key(KeyInput { code, ..key });
+            }
+            KeyCode::Char(c) if self.modify_other_keys && modifier != 1 => {
+                format!("\x1b[27;{modifier};{}~", u32::from(c))
+            }
+            KeyCode::Enter | KeyCode::Tab | KeyCode::Backspace | KeyCode::Escape
+                if self.modify_other_keys && modifier != 1 =>
+            {
+                let c = match key.code {
+                    KeyCode::Enter => 0x0d,
+                    KeyCode::Tab => 0x09,
+                    KeyCode::Backspace => 0x7f,
+                    _ => 0x1b,
+                };
+                format!("\x1b[27;{modifier};{c}~")
+            }
+            KeyCode::Char(c) => {
+                let mut bytes = Vec::new();
+                if key.alt {
+                    bytes.push(0x1b);
+                }
+                if key.ctrl {
+                    bytes.push(control_byte(c)?);
+                } else {
+                    bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                return Some(bytes);
+            }
+            KeyCode::Enter | KeyCode::Tab | KeyCode::Backspace | KeyCode::Escape => {
+                if key.ctrl {
+                    return None;
+                }
+                let b = match key.code {
+                    KeyCode::Enter => b'\r',
+                    KeyCode::Tab => b'\t',
+                    KeyCode::Backspace => 0x7f,
+                    _ => 0x1b,
+                };
+                return Some(if key.alt { vec![0x1b, b] } else { vec![b] });
+            }
+            KeyCode::BackTab if modifier == 1 => "\x1b[Z".to_owned(),
+            KeyCode::BackTab => format!("\x1b[1;{modifier}Z"),
+            KeyCode::Up => cursor('A'),
+            KeyCode::Down => cursor('B'),
+            KeyCode::Right => cursor('C'),
+            KeyCode::Left => cursor('D'),
+            KeyCode::Home => cursor('H'),
+            KeyCode::End => cursor('F'),
+            KeyCode::Insert => tilde(2),
+            KeyCode::Delete => tilde(3),
+            KeyCode::PageUp => tilde(5),
+            KeyCode::PageDown => tilde(6),
+            KeyCode::F(n @ 1..=4) => {
+                let final_byte = (b'P' + n - 1) as char;
+                if modifier == 1 {
+                    format!("\x1bO{final_byte}")
+                } else {
+                    format!("\x1b[1;{modifier}{final_byte}")
+                }
+            }
+            KeyCode::F(n @ 5) => tilde(n + 10),
+            KeyCode::F(n @ 6..=10) => tilde(n + 11),
+            KeyCode::F(n @ 11..=14) => tilde(n + 12),
+            KeyCode::F(n @ 15..=16) => tilde(n + 13),
+            KeyCode::F(n @ 17..=20) => tilde(n + 14),
+            KeyCode::F(_) => return None,
+        };
+        Some(bytes.into_bytes())
+    }
+
+    /// Encodes a mouse input, returning `None` if it cannot be encoded.
+    pub fn encode_mouse(&self, mouse: MouseInput) -> Option<Vec<u8>> {
+        let (mut button, is_release) = mouse_button_code(mouse.event)?;
+        if mouse.shift {
+            button |= 0x04;
+        }
+        if mouse.alt {
+            button |= 0x08;
+        }
+        if mouse.ctrl {
+            button |= 0x10;
+        }
+        let suffix = if is_release { 'm' } else { 'M' };
+
+        match self.mouse_encoding {
+            MouseEncoding::X10 => {
+                if is_release {
+                    // X10 has a single release code for all buttons
+                    button = (button & 0x1c) | 0x03;
+                }
+                let x = u8::try_from(mouse.position.col + 1 + 32).ok()?;
+                let y = u8::try_from(mouse.position.row + 1 + 32).ok()?;
+                Some(vec![0x1b, b'[', b'M', button + 32, x, y])
+            }
+            MouseEncoding::Sgr => {
+                let (x, y) = (mouse.position.col + 1, mouse.position.row + 1);
+                Some(format!("\x1b[<{button};{x};{y}{suffix}").into_bytes())
+            }
+            MouseEncoding::SgrPixels => {
+                let p = mouse.pixel_position?;
+                let (x, y) = (p.x + 1, p.y + 1);
+                Some(format!("\x1b[<{button};{x};{y}{suffix}").into_bytes())
+            }
+        }
+    }
+}
+
+/// Returns the control character sent for Ctrl with `c` (e.g., `0x01` for Ctrl+a).
+fn control_byte(c: char) -> Option<u8> {
+    match c {
+        ' ' => Some(0x00),
+        '@'..='\x7f' => Some(c as u8 & 0x1f),
+        _ => None,
+    }
+}
+
+/// Returns the final byte of the SS3 sequence sent for a keypad key in the application keypad mode.
+fn keypad_final_byte(c: char) -> Option<u8> {
+    match c {
+        '=' => Some(b'X'),
+        '*' | '+' | ',' | '-' | '.' | '/' | '0'..='9' => Some(b'j' + (c as u8 - b'*')),
+        _ => None,
+    }
+}
+
+/// Returns the button code (without modifiers) of a mouse event and whether it is a release.
+fn mouse_button_code(event: MouseEvent) -> Option<(u8, bool)> {
+    let button = |button: MouseButton| match button {
+        MouseButton::Left => Some(0),
+        MouseButton::Middle => Some(1),
+        MouseButton::Right => Some(2),
+        MouseButton::Extra(n @ 8..=11) => Some(0x80 + n - 8),
+        MouseButton::Extra(_) => None,
+    };
+
+    let code = match event {
+        MouseEvent::LeftPress => (0, false),
+        MouseEvent::MiddlePress => (1, false),
+        MouseEvent::RightPress => (2, false),
+        MouseEvent::LeftRelease => (0, true),
+        MouseEvent::MiddleRelease => (1, true),
+        MouseEvent::RightRelease => (2, true),
+        MouseEvent::ExtraPress(n) => (button(MouseButton::Extra(n))?, false),
+        MouseEvent::ExtraRelease(n) => (button(MouseButton::Extra(n))?, true),
+        MouseEvent::Drag(b) => (button(b)? | 0x20, false),
+        MouseEvent::Move => (0x23, false),
+        MouseEvent::ScrollUp => (0x40, false),
+        MouseEvent::ScrollDown => (0x41, false),
+        MouseEvent::ScrollLeft => (0x42, false),
+        MouseEvent::ScrollRight => (0x43, false),
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InputParser, PixelPosition, PixelSize, TerminalPosition};
+
+    fn key(ctrl: bool, alt: bool, code: KeyCode) -> TerminalInput {
+        TerminalInput::Key(KeyInput { ctrl, alt, code })
+    }
+
+    fn decode(bytes: &[u8], pixel_mouse: bool, distinct_keypad_keys: bool) -> Vec<TerminalInput> {
+        let mut parser = InputParser::new();
+        parser.set_pixel_mouse(pixel_mouse);
+        parser.set_distinct_keypad_keys(distinct_keypad_keys);
+        parser.set_cell_pixel_size(PixelSize::width_height(8, 16));
+        parser.feed(bytes);
+        parser.collect()
+    }
+
+    #[test]
+    fn encode_keys() {
+        let encoder = InputEncoder::new();
+        let app = InputEncoder::new().application_cursor();
+        let examples: [(TerminalInput, &[u8], &[u8]); _] = [
+            (key(false, false, KeyCode::Char('a')), b"a", b"a"),
+            (
+                key(false, false, KeyCode::Char('€')),
+                "€".as_bytes(),
+                "€".as_bytes(),
+            ),
+            (key(true, false, KeyCode::Char('a')), b"\x01", b"\x01"),
+            (
+                key(true, true, KeyCode::Char('a')),
+                b"\x1b\x01",
+                b"\x1b\x01",
+            ),
+            (key(false, true, KeyCode::Char('x')), b"\x1bx", b"\x1bx"),
+            (
+                key(false, true, KeyCode::Backspace),
+                b"\x1b\x7f",
+                b"\x1b\x7f",
+            ),
+            (key(false, false, KeyCode::Up), b"\x1b[A", b"\x1bOA"),
+            (key(false, false, KeyCode::End), b"\x1b[F", b"\x1bOF"),
+            (key(true, false, KeyCode::Left), b"\x1b[1;5D", b"\x1b[1;5D"),
+            (key(true, true, KeyCode::PageUp), b"\x1b[5;7~", b"\x1b[5;7~"),
+            (key(false, false, KeyCode::F(1)), b"\x1bOP", b"\x1bOP"),
+            (key(false, true, KeyCode::F(4)), b"\x1b[1;3S", b"\x1b[1;3S"),
+            (key(false, false, KeyCode::F(12)), b"\x1b[24~", b"\x1b[24~"),
+            (key(false, false, KeyCode::BackTab), b"\x1b[Z", b"\x1b[Z"),
+        ];
+        for (input, expected, expected_app) in examples {
+            assert_eq!(encoder.encode(input).as_deref(), Some(expected), "{input}");
+            assert_eq!(app.encode(input).as_deref(), Some(expected_app), "{input}");
+        }
+
+        let app = InputEncoder::new().application_keypad();
+        let examples: [(TerminalInput, &[u8], &[u8]); _] = [
+            (key(false, false, KeyCode::KeypadEnter), b"\r", b"\x1bOM"),
+            (key(false, false, KeyCode::Keypad('+')), b"+", b"\x1bOk"),
+            (key(false, false, KeyCode::Keypad('7')), b"7", b"\x1bOw"),
+            (key(false, false, KeyCode::Keypad('=')), b"=", b"\x1bOX"),
+            (key(false, true, KeyCode::Keypad('1')), b"\x1b1", b"\x1b1"),
+        ];
+        for (input, expected, expected_app) in examples {
+            assert_eq!(encoder.encode(input).as_deref(), Some(expected), "{input}");
+            assert_eq!(app.encode(input).as_deref(), Some(expected_app), "{input}");
+        }
+
+        let mok = InputEncoder::new().modify_other_keys();
+        let examples: [(TerminalInput, &[u8]); _] = [
+            (key(true, false, KeyCode::Char('1')), b"\x1b[27;5;49~"),
+            (key(true, false, KeyCode::Char('A')), b"\x1b[27;5;65~"),
+            (key(false, true, KeyCode::Char('x')), b"\x1b[27;3;120~"),
+            (key(true, false, KeyCode::Enter), b"\x1b[27;5;13~"),
+            (key(true, true, KeyCode::Escape), b"\x1b[27;7;27~"),
+            (key(false, false, KeyCode::Char('a')), b"a"),
+            (key(true, false, KeyCode::Up), b"\x1b[1;5A"),
+        ];
+        for (input, expected) in examples {
+            assert_eq!(mok.encode(input).as_deref(), Some(expected), "{input}");
+        }
+
+        assert_eq!(encoder.encode(key(true, false, KeyCode::Char('1'))), None);
+        assert_eq!(encoder.encode(key(true, false, KeyCode::Enter)), None);
+        assert_eq!(encoder.encode(key(false, false, KeyCode::F(21))), None);
+    }
+
+    #[test]
+    fn encode_mouse() {
+        let mouse = |event, ctrl, pixel_position| MouseInput {
+            event,
+            position: TerminalPosition::row_col(4, 12),
+            ctrl,
+            alt: false,
+            shift: false,
+            pixel_position,
+        };
+        let sgr = InputEncoder::new();
+        let x10 = InputEncoder::new().mouse_encoding(MouseEncoding::X10);
+        let pixels = InputEncoder::new().mouse_encoding(MouseEncoding::SgrPixels);
+
+        let input = mouse(MouseEvent::LeftPress, false, None);
+        assert_eq!(
+            sgr.encode_mouse(input).as_deref(),
+            Some(&b"\x1b[<0;13;5M"[..])
+        );
+        assert_eq!(
+            x10.encode_mouse(input).as_deref(),
+            Some(&b"\x1b[M\x20\x2d\x25"[..])
+        );
+        assert_eq!(pixels.encode_mouse(input), None);
+
+        let input = mouse(
+            MouseEvent::RightRelease,
+            true,
+            Some(PixelPosition::xy(99, 70)),
+        );
+        assert_eq!(
+            sgr.encode_mouse(input).as_deref(),
+            Some(&b"\x1b[<18;13;5m"[..])
+        );
+        assert_eq!(
+            x10.encode_mouse(input).as_deref(),
+            Some(&b"\x1b[M\x33\x2d\x25"[..])
+        );
+        assert_eq!(
+            pixels.encode_mouse(input).as_deref(),
+            Some(&b"\x1b[<18;100;71m"[..])
+        );
+
+        let input = mouse(MouseEvent::Drag(MouseButton::Extra(9)), false, None);
+        assert_eq!(
+            sgr.encode_mouse(input).as_deref(),
+            Some(&b"\x1b[<161;13;5M"[..])
+        );
+
+        let mut input = mouse(MouseEvent::ScrollDown, false, None);
+        input.position = TerminalPosition::row_col(0, 223);
+        assert_eq!(x10.encode_mouse(input), None);
+        assert!(sgr.encode_mouse(input).is_some());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut samples: Vec<Vec<u8>> = Vec::new();
+        for b in 0..=0x7f {
+            samples.push(vec![b]);
+            samples.push(vec![0x1b, b]);
+        }
+        for c in ["é", "€", "\u{1f600}"] {
+            samples.push(c.as_bytes().to_vec());
+        }
+        for m in ["", "1;2", "1;3", "1;5", "1;7", "1;8"] {
+            for f in "ABCDHFZPQRS".chars() {
+                samples.push(format!("\x1b[{m}{f}").into_bytes());
+            }
+        }
+        for n in (1..=8).chain(11..=34) {
+            samples.push(format!("\x1b[{n}~").into_bytes());
+            samples.push(format!("\x1b[{n};6~").into_bytes());
+        }
+        for f in "ABCDHFPQRSMXjklmnopqrstuvwxy".chars() {
+            samples.push(format!("\x1bO{f}").into_bytes());
+        }
+        let mut mok_samples = Vec::new();
+        for m in 2..=8 {
+            for c in [9, 13, 27, 32, 49, 65, 97, 127, 233] {
+                mok_samples.push(format!("\x1b[27;{m};{c}~").into_bytes());
+            }
+        }
+        let mut sgr_samples = Vec::new();
+        let mut x10_samples = Vec::new();
+        for b in 0..=0xff {
+            sgr_samples.push(format!("\x1b[<{b};12;4M").into_bytes());
+            sgr_samples.push(format!("\x1b[<{b};300;200m").into_bytes());
+        }
+        for b in 32..=0xff {
+            x10_samples.push(vec![0x1b, b'[', b'M', b, 44, 36]);
+        }
+
+        let check = |encoder: InputEncoder, samples: &[Vec<u8>], pixel_mouse: bool| {
+            let distinct_keypad_keys = encoder.application_keypad;
+            let decode = |bytes: &[u8]| decode(bytes, pixel_mouse, distinct_keypad_keys);
+            let mut count = 0;
+            for sample in samples {
+                for input in decode(sample) {
+                    let bytes = encoder.encode(input).expect("encodable");
+                    if bytes == b"\x1b" {
+                        // A lone ESC is not decoded until the next byte arrives
+                        continue;
+                    }
+                    assert_eq!(decode(&bytes), [input], "{input}: {bytes:?}");
+                    count += 1;
+                }
+            }
+            count
+        };
+
+        for encoder in [
+            InputEncoder::new(),
+            InputEncoder::new().application_cursor(),
+            InputEncoder::new().application_keypad(),
+            InputEncoder::new().modify_other_keys(),
+        ] {
+            assert!(check(encoder, &samples, false) > 300);
+        }
+        let mok = InputEncoder::new().modify_other_keys();
+        assert!(check(mok, &mok_samples, false) > 50);
+        assert!(check(InputEncoder::new(), &sgr_samples, false) > 200);
+        assert!(check(InputEncoder::new(), &x10_samples, false) > 100);
+        let pixels = InputEncoder::new().mouse_encoding(MouseEncoding::SgrPixels);
+        assert!(check(pixels, &sgr_samples, true) > 200);
+        let x10 = InputEncoder::new().mouse_encoding(MouseEncoding::X10);
+        assert!(check(x10, &x10_samples, false) > 100);
+    }
+}
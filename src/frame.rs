@@ -1,6 +1,6 @@
-use std::{collections::BTreeMap, num::NonZeroUsize};
+use std::{collections::HashMap, num::NonZeroUsize};
 
-use crate::{TerminalPosition, TerminalSize, TerminalStyle};
+use crate::{TerminalHyperlink, TerminalPosition, TerminalSize, TerminalStyle};
 
 /// A frame buffer representing the terminal display state.
 ///
@@ -12,6 +12,7 @@ use crate::{TerminalPosition, TerminalSize, TerminalStyle};
 /// This struct serves as the primary drawing surface for terminal UIs, allowing
 /// you to:
 /// - Write text with different styles using the `write!()` macro
+/// - Attach hyperlinks to text using [`TerminalHyperlink`]
 /// - Compose multiple frames together
 /// - Draw frames to the terminal using `Terminal::draw()`
 ///
@@ -19,6 +20,9 @@ use crate::{TerminalPosition, TerminalSize, TerminalStyle};
 ///
 /// [`TerminalFrame`] implements the [`std::fmt::Write`] trait, which allows using
 /// the `write!()` and `writeln!()` macros to add content to the frame with styling.
+/// Escape sequences are interpreted only if they come from [`TerminalStyle`] or
+/// [`TerminalHyperlink`]. Other OSC sequences are dropped and make the write fail
+/// with [`std::fmt::Error`].
 ///
 /// # Drawing Frames
 ///
@@ -51,9 +55,13 @@ use crate::{TerminalPosition, TerminalSize, TerminalStyle};
 #[derive(Debug, Default, Clone)]
 pub struct TerminalFrame<W = FixedCharWidthEstimator> {
     size: TerminalSize,
-    data: BTreeMap<TerminalPosition, TerminalChar>,
+    data: Vec<Option<TerminalChar>>,
+    max_char_width: usize,
     tail: TerminalPosition,
     current_style: TerminalStyle,
+    current_link: Option<u32>,
+    links: Vec<TerminalHyperlink>,
+    link_indices: HashMap<TerminalHyperlink, u32>,
     escape_sequence: String,
     char_width_estimator: W,
 }
@@ -70,9 +78,13 @@ impl<W> TerminalFrame<W> {
     pub fn with_char_width_estimator(size: TerminalSize, char_width_estimator: W) -> Self {
         Self {
             size,
-            data: BTreeMap::new(),
+            data: vec![None; size.rows * size.cols],
+            max_char_width: 0,
             tail: TerminalPosition::ZERO,
             current_style: TerminalStyle::new(),
+            current_link: None,
+            links: Vec::new(),
+            link_indices: HashMap::new(),
             escape_sequence: String::new(),
             char_width_estimator,
         }
@@ -104,6 +116,51 @@ impl<W> TerminalFrame<W> {
         self.tail
     }
 
+    /// Removes all characters from this frame and moves the cursor back to the origin.
+    ///
+    /// The underlying buffer is kept, so clearing and redrawing a frame of the same size
+    /// does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fmt::Write;
+    /// use tuinix::{TerminalFrame, TerminalPosition, TerminalSize};
+    ///
+    /// let mut frame: TerminalFrame = TerminalFrame::new(TerminalSize::rows_cols(10, 20));
+    /// write!(frame, "Hello")?;
+    ///
+    /// frame.clear();
+    /// assert_eq!(frame.cursor(), TerminalPosition::ZERO);
+    /// # Ok::<(), std::fmt::Error>(())
+    /// ```
+    pub fn clear(&mut self) {
+        self.data.fill(None);
+        self.reset();
+    }
+
+    /// Clears this frame and changes its size.
+    ///
+    /// The underlying buffer is reused and only grows if the new size has more cells than
+    /// any size this frame has had before.
+    pub fn resize(&mut self, size: TerminalSize) {
+        self.size = size;
+        self.data.clear();
+        self.data.resize(size.rows * size.cols, None);
+        self.reset();
+    }
+
+    /// Resets the state other than the characters, including the hyperlink table.
+    fn reset(&mut self) {
+        self.max_char_width = 0;
+        self.tail = TerminalPosition::ZERO;
+        self.current_style = TerminalStyle::new();
+        self.current_link = None;
+        self.links.clear();
+        self.link_indices.clear();
+        self.escape_sequence.clear();
+    }
+
     /// Draws the contents of another frame onto this frame at the specified position.
     ///
     /// This method copies all the characters from the source frame and positions them
@@ -132,33 +189,29 @@ impl<W> TerminalFrame<W> {
     /// # Ok::<(), std::fmt::Error>(())
     /// ```
     pub fn draw<X>(&mut self, position: TerminalPosition, frame: &TerminalFrame<X>) {
+        self.max_char_width = self.max_char_width.max(frame.max_char_width);
         for (src_pos, c) in frame.chars() {
             let target_pos = position + src_pos;
             if !self.size.contains(target_pos) {
                 continue;
             }
 
-            if let Some((&prev_pos, prev_c)) = self.data.range(..target_pos).next_back() {
-                let end_pos = prev_pos + TerminalPosition::col(prev_c.width.get());
-                if target_pos < end_pos {
-                    self.data.remove(&prev_pos);
-                }
+            if let Some((prev_pos, _)) = self.find_covering_char(target_pos) {
+                self.set_char(prev_pos, None);
             }
             for i in 0..c.width.get() {
-                self.data.remove(&(target_pos + TerminalPosition::col(i)));
+                self.set_char(target_pos + TerminalPosition::col(i), None);
             }
-            self.data.insert(target_pos, c);
+            let link = frame.link(c).and_then(|link| self.intern_link(link));
+            self.set_char(target_pos, Some(TerminalChar { link, ..c }));
         }
     }
 
     pub(crate) fn get_char(&self, position: TerminalPosition) -> Option<TerminalChar> {
-        if let Some(ch) = self.data.get(&position).copied() {
+        if let Some(ch) = self.char_at(position) {
             // Character exists at this exact position - return it
             Some(ch)
-        } else if let Some((pos, prev)) = self.data.range(..position).next_back()
-            && position.row == pos.row
-            && position.col < pos.col + prev.width.get()
-        {
+        } else if self.find_covering_char(position).is_some() {
             // Position falls within a wide character's display area but not at its starting position.
             // Return None to indicate this position is occupied by a multi-column character
             // that starts at an earlier column.
@@ -183,7 +236,7 @@ impl<W> TerminalFrame<W> {
                 }
 
                 next_pos = pos;
-                if let Some(c) = self.data.get(&pos).copied() {
+                if let Some(c) = self.char_at(pos) {
                     next_pos.col += c.width.get();
                     Some((pos, c))
                 } else {
@@ -198,12 +251,89 @@ impl<W> TerminalFrame<W> {
         TerminalFrame {
             size: self.size,
             data: self.data,
+            max_char_width: self.max_char_width,
             tail: self.tail,
             current_style: self.current_style,
+            current_link: self.current_link,
+            links: self.links,
+            link_indices: self.link_indices,
             escape_sequence: self.escape_sequence,
             char_width_estimator: FixedCharWidthEstimator,
         }
     }
+
+    /// Makes this frame hold the same characters as `other`, reusing the existing buffer.
+    pub(crate) fn copy_from<X>(&mut self, other: &TerminalFrame<X>) {
+        self.size = other.size;
+        self.data.clone_from(&other.data);
+        self.max_char_width = other.max_char_width;
+        self.links.clone_from(&other.links);
+        self.link_indices.clone_from(&other.link_indices);
+        self.tail = other.tail;
+    }
+
+    /// Exchanges the character buffers (and sizes) of two frames without copying.
+    pub(crate) fn swap_chars<X>(&mut self, other: &mut TerminalFrame<X>) {
+        std::mem::swap(&mut self.size, &mut other.size);
+        std::mem::swap(&mut self.data, &mut other.data);
+        std::mem::swap(&mut self.max_char_width, &mut other.max_char_width);
+        std::mem::swap(&mut self.links, &mut other.links);
+        std::mem::swap(&mut self.link_indices, &mut other.link_indices);
+        std::mem::swap(&mut self.tail, &mut other.tail);
+    }
+
+    /// Returns the hyperlink attached to a character of this frame.
+    pub(crate) fn link(&self, c: TerminalChar) -> Option<&TerminalHyperlink> {
+        c.link.map(|i| &self.links[i as usize])
+    }
+
+    /// Returns the index of `link` in the hyperlink table, adding it if needed.
+    ///
+    /// Cells only store this index, so that they stay small and cheap to compare.
+    fn intern_link(&mut self, link: &TerminalHyperlink) -> Option<u32> {
+        if link.uri.is_empty() {
+            return None;
+        }
+        if let Some(&i) = self.link_indices.get(link) {
+            return Some(i);
+        }
+        let i = self.links.len() as u32;
+        self.links.push(link.clone());
+        self.link_indices.insert(link.clone(), i);
+        Some(i)
+    }
+
+    fn char_at(&self, position: TerminalPosition) -> Option<TerminalChar> {
+        if !self.size.contains(position) {
+            return None;
+        }
+        self.data[position.row * self.size.cols + position.col]
+    }
+
+    fn set_char(&mut self, position: TerminalPosition, c: Option<TerminalChar>) {
+        if !self.size.contains(position) {
+            return;
+        }
+        self.data[position.row * self.size.cols + position.col] = c;
+    }
+
+    /// Returns the wide character that starts before `position` on the same row and covers it.
+    fn find_covering_char(
+        &self,
+        position: TerminalPosition,
+    ) -> Option<(TerminalPosition, TerminalChar)> {
+        // No character can extend further than the widest one written to this frame.
+        let start = position
+            .col
+            .saturating_sub(self.max_char_width.saturating_sub(1));
+        for col in (start..position.col).rev() {
+            let pos = TerminalPosition::row_col(position.row, col);
+            if let Some(c) = self.char_at(pos) {
+                return (position.col < col + c.width.get()).then_some((pos, c));
+            }
+        }
+        None
+    }
 }
 
 impl<W: EstimateCharWidth> std::fmt::Write for TerminalFrame<W> {
@@ -211,7 +341,17 @@ impl<W: EstimateCharWidth> std::fmt::Write for TerminalFrame<W> {
         for c in s.chars() {
             if !self.escape_sequence.is_empty() {
                 self.escape_sequence.push(c);
-                if c.is_ascii_alphabetic() {
+                if self.escape_sequence.starts_with("\x1b]") {
+                    // OSC sequences end with BEL or ST (ESC \)
+                    if c == '\x07' || self.escape_sequence.ends_with("\x1b\\") {
+                        let link = self.escape_sequence.parse::<TerminalHyperlink>();
+                        self.escape_sequence.clear();
+                        // Other OSC sequences (e.g., setting the window title) are dropped,
+                        // as they do not affect the frame contents
+                        let link = link.map_err(|_| std::fmt::Error)?;
+                        self.current_link = self.intern_link(&link);
+                    }
+                } else if c.is_ascii_alphabetic() {
                     self.current_style = self
                         .escape_sequence
                         .parse()
@@ -234,13 +374,15 @@ impl<W: EstimateCharWidth> std::fmt::Write for TerminalFrame<W> {
             };
 
             if self.tail.row < self.size.rows && self.tail.col + width.get() <= self.size.cols {
-                self.data.insert(
+                self.max_char_width = self.max_char_width.max(width.get());
+                self.set_char(
                     self.tail,
-                    TerminalChar {
+                    Some(TerminalChar {
                         style: self.current_style,
                         width,
                         value: c,
-                    },
+                        link: self.current_link,
+                    }),
                 );
             }
             self.tail.col += width.get();
@@ -302,6 +444,9 @@ pub(crate) struct TerminalChar {
     pub style: TerminalStyle,
     pub width: NonZeroUsize,
     pub value: char,
+
+    /// Index into the hyperlink table of the frame that owns this character.
+    pub link: Option<u32>,
 }
 
 impl TerminalChar {
@@ -309,7 +454,12 @@ impl TerminalChar {
         style: TerminalStyle::new(),
         width: NonZeroUsize::MIN,
         value: ' ',
+        link: None,
     };
+
+    pub fn without_link(self) -> Self {
+        Self { link: None, ..self }
+    }
 }
 
 #[cfg(test)]
@@ -358,4 +508,90 @@ mod tests {
         assert_eq!(chars[2].0, TerminalPosition::row_col(0, 4));
         assert_eq!(chars[3].0, TerminalPosition::row_col(0, 6));
     }
+
+    #[test]
+    fn draw_over_wide_chars() {
+        let size = TerminalSize::rows_cols(2, 10);
+        let mut frame = TerminalFrame::with_char_width_estimator(size, UnicodeCharWidthEstimator);
+        write!(frame, "おはよう").unwrap();
+
+        // Overwrite the second column, which is covered by 'お'
+        let mut sub_frame: TerminalFrame = TerminalFrame::new(TerminalSize::rows_cols(1, 1));
+        write!(sub_frame, "x").unwrap();
+        frame.draw(TerminalPosition::col(1), &sub_frame);
+
+        assert_eq!(
+            frame.get_char(TerminalPosition::col(0)),
+            Some(TerminalChar::BLANK)
+        );
+        assert_eq!(
+            frame.get_char(TerminalPosition::col(1)).map(|c| c.value),
+            Some('x')
+        );
+        assert_eq!(
+            frame.get_char(TerminalPosition::col(2)).map(|c| c.value),
+            Some('は')
+        );
+        assert_eq!(frame.get_char(TerminalPosition::col(3)), None);
+    }
+
+    #[test]
+    fn clear_and_resize() {
+        let size = TerminalSize::rows_cols(3, 4);
+        let mut frame: TerminalFrame = TerminalFrame::new(size);
+        writeln!(frame, "abcd").unwrap();
+        write!(frame, "efgh").unwrap();
+
+        let capacity = frame.data.capacity();
+        frame.clear();
+        assert_eq!(frame.cursor(), TerminalPosition::ZERO);
+        assert!(frame.chars().all(|(_, c)| c == TerminalChar::BLANK));
+
+        let link = TerminalHyperlink::new("https://example.com").unwrap();
+        write!(frame, "{link}ab").unwrap();
+        frame.resize(TerminalSize::rows_cols(2, 5));
+        assert_eq!(frame.size(), TerminalSize::rows_cols(2, 5));
+        assert_eq!(frame.cursor(), TerminalPosition::ZERO);
+        assert_eq!(frame.chars().count(), 10);
+        assert!(frame.chars().all(|(_, c)| c == TerminalChar::BLANK));
+        assert_eq!(frame.data.capacity(), capacity);
+        assert!(frame.links.is_empty() && frame.link_indices.is_empty());
+        assert_eq!(frame.current_link, None);
+    }
+
+    #[test]
+    fn hyperlinks() {
+        let link = TerminalHyperlink::new("https://example.com").unwrap();
+        let end = TerminalHyperlink::END;
+
+        let mut frame: TerminalFrame = TerminalFrame::new(TerminalSize::rows_cols(2, 10));
+        write!(frame, "a{link}bc{end}d").unwrap();
+        let links: Vec<_> = frame.chars().take(4).map(|(_, c)| frame.link(c)).collect();
+        assert_eq!(links, [None, Some(&link), Some(&link), None]);
+
+        // Links are re-interned when composing frames
+        let other = TerminalHyperlink::new("https://example.org")
+            .and_then(|link| link.id("1"))
+            .unwrap();
+        let mut main_frame: TerminalFrame = TerminalFrame::new(TerminalSize::rows_cols(2, 10));
+        write!(main_frame, "{other}xyz{end}").unwrap();
+        main_frame.draw(TerminalPosition::row(1), &frame);
+        let c = main_frame
+            .get_char(TerminalPosition::row_col(0, 1))
+            .unwrap();
+        assert_eq!(main_frame.link(c), Some(&other));
+        let c = main_frame
+            .get_char(TerminalPosition::row_col(1, 1))
+            .unwrap();
+        assert_eq!(main_frame.link(c), Some(&link));
+        assert_eq!(main_frame.links.len(), 2);
+        assert_eq!(main_frame.link_indices.len(), 2);
+
+        // Other OSC sequences are dropped with an error instead of a panic
+        let mut frame: TerminalFrame = TerminalFrame::new(TerminalSize::rows_cols(1, 10));
+        assert!(write!(frame, "a\x1b]0;title\x07").is_err());
+        write!(frame, "b").unwrap();
+        let text: String = frame.chars().map(|(_, c)| c.value).collect();
+        assert_eq!(text.trim_end(), "ab");
+    }
 }
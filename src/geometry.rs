@@ -287,3 +287,68 @@ impl TerminalRegion {
             .expand_right(amount)
     }
 }
+
+/// Position in pixels within a terminal, 0-indexed from the top-left corner.
+///
+/// This is reported for mouse input in SGR-pixels mode
+/// (see [`Terminal::set_pixel_mouse_input()`](crate::Terminal::set_pixel_mouse_input)).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PixelPosition {
+    /// Horizontal coordinate.
+    pub x: usize,
+
+    /// Vertical coordinate.
+    pub y: usize,
+}
+
+impl PixelPosition {
+    /// Origin position (0,0).
+    pub const ZERO: Self = Self::xy(0, 0);
+
+    /// Makes a new position with the specified pixel coordinates.
+    pub const fn xy(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the position of the cell containing this pixel, given the size of a cell.
+    ///
+    /// If `cell_size` is empty, this returns [`TerminalPosition::ZERO`].
+    pub const fn to_cell_position(self, cell_size: PixelSize) -> TerminalPosition {
+        if cell_size.is_empty() {
+            return TerminalPosition::ZERO;
+        }
+        TerminalPosition::row_col(self.y / cell_size.height, self.x / cell_size.width)
+    }
+}
+
+/// Size in pixels, typically of a terminal cell
+/// (see [`Terminal::cell_pixel_size()`](crate::Terminal::cell_pixel_size)).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PixelSize {
+    /// Width in pixels.
+    pub width: usize,
+
+    /// Height in pixels.
+    pub height: usize,
+}
+
+impl PixelSize {
+    /// A size with zero width and zero height.
+    pub const EMPTY: Self = Self::width_height(0, 0);
+
+    /// Makes a new size with the specified width and height in pixels.
+    pub const fn width_height(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+
+    /// Returns `true` if the width or height is zero.
+    pub const fn is_empty(self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Returns the pixel position of the top-left corner of a cell, assuming this is
+    /// the size of a cell.
+    pub const fn cell_origin(self, position: TerminalPosition) -> PixelPosition {
+        PixelPosition::xy(position.col * self.width, position.row * self.height)
+    }
+}
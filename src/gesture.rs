@@ -0,0 +1,493 @@
+use std::time::{Duration, Instant};
+
+use crate::{MouseButton, MouseEvent, MouseInput, TerminalInput, TerminalPosition};
+
+/// Thresholds used by [`GestureRecognizer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GestureConfig {
+    /// Maximum time between a click and the next press for the press to continue
+    /// a multi-click (e.g., a double-click).
+    pub multi_click_interval: Duration,
+
+    /// Maximum distance in cells between consecutive clicks of a multi-click.
+    pub multi_click_distance: usize,
+
+    /// Minimum distance in cells the pointer has to move while a button is held down
+    /// before a drag starts.
+    pub drag_threshold: usize,
+
+    /// Minimum time a button has to be held down without dragging to be a long press.
+    pub long_press_duration: Duration,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            multi_click_interval: Duration::from_millis(500),
+            multi_click_distance: 1,
+            drag_threshold: 1,
+            long_press_duration: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Higher-level mouse event recognized by [`GestureRecognizer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GestureEvent {
+    /// A button was pressed and released without dragging.
+    Click {
+        /// The clicked button.
+        button: MouseButton,
+        /// The position where the button was pressed.
+        position: TerminalPosition,
+        /// Number of consecutive clicks (1 for a single click, 2 for a double-click, and so on).
+        count: usize,
+        /// The time of the release.
+        timestamp: Instant,
+    },
+
+    /// The pointer moved beyond the drag threshold while a button was held down.
+    DragStart {
+        /// The held button.
+        button: MouseButton,
+        /// The position where the button was pressed.
+        origin: TerminalPosition,
+        /// The current pointer position.
+        position: TerminalPosition,
+        /// The time of the motion.
+        timestamp: Instant,
+    },
+
+    /// The pointer moved during a drag.
+    DragMove {
+        /// The held button.
+        button: MouseButton,
+        /// The position where the button was pressed.
+        origin: TerminalPosition,
+        /// The current pointer position.
+        position: TerminalPosition,
+        /// The time of the motion.
+        timestamp: Instant,
+    },
+
+    /// The button was released, ending a drag.
+    DragEnd {
+        /// The released button.
+        button: MouseButton,
+        /// The position where the button was pressed.
+        origin: TerminalPosition,
+        /// The position where the button was released.
+        position: TerminalPosition,
+        /// The time of the release.
+        timestamp: Instant,
+    },
+
+    /// A button was held down without dragging for [`GestureConfig::long_press_duration`].
+    ///
+    /// The subsequent release does not produce a click.
+    LongPress {
+        /// The held button.
+        button: MouseButton,
+        /// The position where the button was pressed.
+        position: TerminalPosition,
+        /// The time the long press was recognized.
+        timestamp: Instant,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    button: MouseButton,
+    origin: TerminalPosition,
+    timestamp: Instant,
+    dragging: bool,
+    long_pressed: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LastClick {
+    button: MouseButton,
+    position: TerminalPosition,
+    timestamp: Instant,
+    count: usize,
+}
+
+/// Recognizer of clicks, multi-clicks, drags and long presses from [`TerminalInput`]s.
+///
+/// Inputs are fed with explicit timestamps, so that recognition does not depend on
+/// the wall clock and is reproducible.
+/// As long presses are recognized by the passage of time rather than by inputs,
+/// [`GestureRecognizer::handle_timeout()`] should be called when
+/// [`GestureRecognizer::next_deadline()`] is reached.
+///
+/// Drags are only reported if the terminal reports motion while a button is held down
+/// (see [`MouseTracking`](crate::MouseTracking)).
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Instant;
+/// use tuinix::{GestureEvent, GestureRecognizer, Terminal, TerminalEvent};
+///
+/// let mut terminal = Terminal::new()?;
+/// terminal.enable_mouse_input()?;
+///
+/// let mut gestures = GestureRecognizer::default();
+/// loop {
+///     let timeout = gestures
+///         .next_deadline()
+///         .map(|deadline| deadline.saturating_duration_since(Instant::now()));
+///     let gesture = match terminal.poll_event(&[], &[], timeout)? {
+///         Some(TerminalEvent::Input(input)) => gestures.handle_input(input, Instant::now()),
+///         None => gestures.handle_timeout(Instant::now()),
+///         _ => None,
+///     };
+///     if let Some(GestureEvent::Click { count: 2, position, .. }) = gesture {
+///         println!("Double-clicked at ({}, {})", position.col, position.row);
+///     }
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct GestureRecognizer {
+    config: GestureConfig,
+    press: Option<Press>,
+    last_click: Option<LastClick>,
+}
+
+impl GestureRecognizer {
+    /// Makes a new recognizer with the given thresholds.
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            press: None,
+            last_click: None,
+        }
+    }
+
+    /// Returns the thresholds of this recognizer.
+    pub fn config(&self) -> &GestureConfig {
+        &self.config
+    }
+
+    /// Processes an input received at `timestamp` and returns the recognized gesture, if any.
+    ///
+    /// Non-mouse inputs are ignored.
+    pub fn handle_input(
+        &mut self,
+        input: TerminalInput,
+        timestamp: Instant,
+    ) -> Option<GestureEvent> {
+        let TerminalInput::Mouse(mouse) = input else {
+            return None;
+        };
+        self.handle_mouse_input(mouse, timestamp)
+    }
+
+    /// Processes a mouse input received at `timestamp` and returns the recognized gesture, if any.
+    pub fn handle_mouse_input(
+        &mut self,
+        mouse: MouseInput,
+        timestamp: Instant,
+    ) -> Option<GestureEvent> {
+        let position = mouse.position;
+        match mouse.event {
+            MouseEvent::LeftPress => self.handle_press(MouseButton::Left, position, timestamp),
+            MouseEvent::MiddlePress => self.handle_press(MouseButton::Middle, position, timestamp),
+            MouseEvent::RightPress => self.handle_press(MouseButton::Right, position, timestamp),
+            MouseEvent::ExtraPress(n) => {
+                self.handle_press(MouseButton::Extra(n), position, timestamp)
+            }
+            MouseEvent::Drag(_) => self.handle_drag(position, timestamp),
+            // The X10 encoding does not tell which button was released,
+            // so any release ends the current press
+            MouseEvent::LeftRelease
+            | MouseEvent::MiddleRelease
+            | MouseEvent::RightRelease
+            | MouseEvent::ExtraRelease(_) => self.handle_release(position, timestamp),
+            MouseEvent::Move
+            | MouseEvent::ScrollUp
+            | MouseEvent::ScrollDown
+            | MouseEvent::ScrollLeft
+            | MouseEvent::ScrollRight => None,
+        }
+    }
+
+    /// Recognizes a long press if its duration has elapsed at `now`.
+    pub fn handle_timeout(&mut self, now: Instant) -> Option<GestureEvent> {
+        let press = self.press.as_mut()?;
+        if press.dragging
+            || press.long_pressed
+            || now.saturating_duration_since(press.timestamp) < self.config.long_press_duration
+        {
+            return None;
+        }
+
+        press.long_pressed = true;
+        Some(GestureEvent::LongPress {
+            button: press.button,
+            position: press.origin,
+            timestamp: now,
+        })
+    }
+
+    /// Returns the time at which [`GestureRecognizer::handle_timeout()`] should be called
+    /// to recognize a pending long press, if any.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let press = self.press.as_ref()?;
+        if press.dragging || press.long_pressed {
+            return None;
+        }
+        Some(press.timestamp + self.config.long_press_duration)
+    }
+
+    fn handle_press(
+        &mut self,
+        button: MouseButton,
+        position: TerminalPosition,
+        timestamp: Instant,
+    ) -> Option<GestureEvent> {
+        self.press = Some(Press {
+            button,
+            origin: position,
+            timestamp,
+            dragging: false,
+            long_pressed: false,
+        });
+        None
+    }
+
+    fn handle_drag(
+        &mut self,
+        position: TerminalPosition,
+        timestamp: Instant,
+    ) -> Option<GestureEvent> {
+        let press = self.press.as_mut()?;
+        if press.dragging {
+            return Some(GestureEvent::DragMove {
+                button: press.button,
+                origin: press.origin,
+                position,
+                timestamp,
+            });
+        }
+        if distance(press.origin, position) < self.config.drag_threshold {
+            return None;
+        }
+
+        press.dragging = true;
+        self.last_click = None;
+        Some(GestureEvent::DragStart {
+            button: press.button,
+            origin: press.origin,
+            position,
+            timestamp,
+        })
+    }
+
+    fn handle_release(
+        &mut self,
+        position: TerminalPosition,
+        timestamp: Instant,
+    ) -> Option<GestureEvent> {
+        let press = self.press.take()?;
+        if press.dragging {
+            return Some(GestureEvent::DragEnd {
+                button: press.button,
+                origin: press.origin,
+                position,
+                timestamp,
+            });
+        }
+        if press.long_pressed {
+            return None;
+        }
+        if timestamp.saturating_duration_since(press.timestamp) >= self.config.long_press_duration {
+            // The deadline passed without `handle_timeout()` being called
+            self.last_click = None;
+            return Some(GestureEvent::LongPress {
+                button: press.button,
+                position: press.origin,
+                timestamp,
+            });
+        }
+
+        let count = match self.last_click {
+            Some(last)
+                if last.button == press.button
+                    && press.timestamp.saturating_duration_since(last.timestamp)
+                        <= self.config.multi_click_interval
+                    && distance(last.position, press.origin)
+                        <= self.config.multi_click_distance =>
+            {
+                last.count + 1
+            }
+            _ => 1,
+        };
+        self.last_click = Some(LastClick {
+            button: press.button,
+            position: press.origin,
+            timestamp,
+            count,
+        });
+        Some(GestureEvent::Click {
+            button: press.button,
+            position: press.origin,
+            count,
+            timestamp,
+        })
+    }
+}
+
+/// Returns the Chebyshev distance between two positions, in cells.
+fn distance(a: TerminalPosition, b: TerminalPosition) -> usize {
+    a.row.abs_diff(b.row).max(a.col.abs_diff(b.col))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mouse(event: MouseEvent, row: usize, col: usize) -> TerminalInput {
+        TerminalInput::Mouse(MouseInput {
+            event,
+            position: TerminalPosition::row_col(row, col),
+            ctrl: false,
+            alt: false,
+            shift: false,
+            pixel_position: None,
+        })
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn multi_clicks() {
+        let mut recognizer = GestureRecognizer::default();
+        let t0 = Instant::now();
+
+        let mut clicks = Vec::new();
+        for (i, col) in [5, 5, 6].into_iter().enumerate() {
+            let t = t0 + ms(i as u64 * 200);
+            assert_eq!(
+                recognizer.handle_input(mouse(MouseEvent::LeftPress, 1, col), t),
+                None
+            );
+            clicks
+                .push(recognizer.handle_input(mouse(MouseEvent::LeftRelease, 1, col), t + ms(50)));
+        }
+        let counts: Vec<_> = clicks
+            .iter()
+            .map(|c| match c {
+                Some(GestureEvent::Click { count, .. }) => *count,
+                _ => 0,
+            })
+            .collect();
+        assert_eq!(counts, [1, 2, 3]);
+
+        // Too late for a multi-click
+        let t = t0 + ms(2000);
+        recognizer.handle_input(mouse(MouseEvent::LeftPress, 1, 6), t);
+        assert!(matches!(
+            recognizer.handle_input(mouse(MouseEvent::LeftRelease, 1, 6), t + ms(50)),
+            Some(GestureEvent::Click { count: 1, .. })
+        ));
+
+        // A different button starts a new sequence
+        let t = t + ms(100);
+        recognizer.handle_input(mouse(MouseEvent::RightPress, 1, 6), t);
+        assert!(matches!(
+            recognizer.handle_input(mouse(MouseEvent::RightRelease, 1, 6), t + ms(50)),
+            Some(GestureEvent::Click {
+                button: MouseButton::Right,
+                count: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn drag() {
+        let config = GestureConfig {
+            drag_threshold: 2,
+            ..GestureConfig::default()
+        };
+        let mut recognizer = GestureRecognizer::new(config);
+        let t0 = Instant::now();
+        let drag = MouseEvent::Drag(MouseButton::Left);
+        let origin = TerminalPosition::row_col(3, 3);
+
+        recognizer.handle_input(mouse(MouseEvent::LeftPress, 3, 3), t0);
+        assert_eq!(
+            recognizer.handle_input(mouse(drag, 3, 4), t0 + ms(10)),
+            None
+        );
+        assert_eq!(
+            recognizer.handle_input(mouse(drag, 3, 5), t0 + ms(20)),
+            Some(GestureEvent::DragStart {
+                button: MouseButton::Left,
+                origin,
+                position: TerminalPosition::row_col(3, 5),
+                timestamp: t0 + ms(20),
+            })
+        );
+        assert_eq!(recognizer.next_deadline(), None);
+        assert_eq!(
+            recognizer.handle_input(mouse(drag, 4, 5), t0 + ms(30)),
+            Some(GestureEvent::DragMove {
+                button: MouseButton::Left,
+                origin,
+                position: TerminalPosition::row_col(4, 5),
+                timestamp: t0 + ms(30),
+            })
+        );
+        assert_eq!(
+            recognizer.handle_input(mouse(MouseEvent::LeftRelease, 4, 6), t0 + ms(900)),
+            Some(GestureEvent::DragEnd {
+                button: MouseButton::Left,
+                origin,
+                position: TerminalPosition::row_col(4, 6),
+                timestamp: t0 + ms(900),
+            })
+        );
+
+        // Motion without a press is ignored
+        assert_eq!(
+            recognizer.handle_input(mouse(drag, 4, 9), t0 + ms(950)),
+            None
+        );
+    }
+
+    #[test]
+    fn long_press() {
+        let mut recognizer = GestureRecognizer::default();
+        let t0 = Instant::now();
+
+        recognizer.handle_input(mouse(MouseEvent::LeftPress, 2, 2), t0);
+        assert_eq!(recognizer.next_deadline(), Some(t0 + ms(500)));
+        assert_eq!(recognizer.handle_timeout(t0 + ms(499)), None);
+        assert_eq!(
+            recognizer.handle_timeout(t0 + ms(500)),
+            Some(GestureEvent::LongPress {
+                button: MouseButton::Left,
+                position: TerminalPosition::row_col(2, 2),
+                timestamp: t0 + ms(500),
+            })
+        );
+        assert_eq!(recognizer.handle_timeout(t0 + ms(600)), None);
+        assert_eq!(
+            recognizer.handle_input(mouse(MouseEvent::LeftRelease, 2, 2), t0 + ms(700)),
+            None
+        );
+
+        // Recognized on release if the deadline was missed
+        let t = t0 + ms(1000);
+        recognizer.handle_input(mouse(MouseEvent::LeftPress, 2, 2), t);
+        assert!(matches!(
+            recognizer.handle_input(mouse(MouseEvent::LeftRelease, 2, 2), t + ms(800)),
+            Some(GestureEvent::LongPress { .. })
+        ));
+    }
+}
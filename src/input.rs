@@ -1,8 +1,10 @@
-use std::io::Read;
+use std::{collections::VecDeque, fmt::Display, io::Read, str::FromStr, time::Instant};
 
-use crate::TerminalPosition;
+use crate::{PixelPosition, PixelSize, TerminalPosition};
 
 /// User input.
+///
+/// The textual form (see [`Display`] and [`FromStr`]) is that of [`KeyInput`] or [`MouseInput`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum TerminalInput {
     /// Keyboard input.
@@ -13,6 +15,21 @@ pub enum TerminalInput {
 }
 
 /// Keyboard input.
+///
+/// # Textual form
+///
+/// [`Display`] and [`FromStr`] use a notation like `Ctrl+Alt+PageUp` or `Ctrl+a`:
+/// the key code (see [`KeyCode`]) prefixed by `Ctrl+` and/or `Alt+`.
+/// When parsing, modifiers are matched case-insensitively, and the Emacs-style
+/// `C-` (Ctrl) and `M-` or `A-` (Alt) are also accepted (e.g., `C-M-x`).
+///
+/// ```
+/// use tuinix::{KeyCode, KeyInput};
+///
+/// let key = KeyInput { ctrl: true, alt: true, code: KeyCode::PageUp };
+/// assert_eq!(key.to_string(), "Ctrl+Alt+PageUp");
+/// assert_eq!("C-M-PageUp".parse(), Ok(key));
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct KeyInput {
     /// Indicates whether the Ctrl modifier key was pressed during the input.
@@ -26,6 +43,20 @@ pub struct KeyInput {
 }
 
 /// Key code.
+///
+/// # Textual form
+///
+/// [`Display`] writes a printable character as it is (e.g., `a` or `+`), and other keys
+/// by name (`Enter`, `Escape`, `Backspace`, `Tab`, `BackTab`, `Delete`, `Insert`, `Up`,
+/// `Down`, `Left`, `Right`, `Home`, `End`, `PageUp`, `PageDown`, `F1`, ...).
+/// Keypad keys are written as `KeypadEnter` or `Keypad` followed by the character
+/// (e.g., `Keypad5` or `Keypad+`).
+/// A space is written as `Space`, and other whitespace and control characters
+/// as their code points (e.g., `U+007F`).
+///
+/// [`FromStr`] accepts these forms (except `F0`), with names matched case-insensitively,
+/// and the aliases `Return`, `Ret`, `CR`, `Esc`, `BS`, `S-Tab`, `Del`, `Ins`, `PgUp`, `PgDn`
+/// and `Spc`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum KeyCode {
     /// Enter key.
@@ -58,11 +89,43 @@ pub enum KeyCode {
     PageUp,
     /// Page Down key.
     PageDown,
+    /// Function key (e.g., `F(1)` for F1).
+    F(u8),
+    /// Keypad key other than Enter, identified by its character (e.g., `Keypad('5')` or `Keypad('+')`).
+    ///
+    /// Keypad keys are only told apart from the main keys in the application keypad mode
+    /// with distinct keypad keys enabled
+    /// (see [`Terminal::set_application_keypad()`](crate::Terminal::set_application_keypad)).
+    Keypad(char),
+    /// Keypad Enter key.
+    ///
+    /// This is only reported under the same conditions as [`KeyCode::Keypad`].
+    KeypadEnter,
     /// Character key.
     Char(char),
 }
 
 /// Mouse input.
+///
+/// # Textual form
+///
+/// [`Display`] and [`FromStr`] use a notation like `Ctrl+LeftPress@12,4`:
+/// the event, optionally prefixed by `Ctrl+`, `Alt+` and/or `Shift+`, followed by
+/// `@COL,ROW`. The pixel position, if any, follows as `/X,Y` (e.g., `Move@12,4/100,67`).
+///
+/// Events are written as `Move`, `ScrollUp`, `ScrollDown`, `ScrollLeft`, `ScrollRight`,
+/// or a button (`Left`, `Middle`, `Right`, or `Button8` and so on for extra buttons)
+/// followed by `Press`, `Release` or `Drag` (e.g., `RightDrag`, `Button8Press`).
+///
+/// ```
+/// use tuinix::{MouseEvent, MouseInput, TerminalPosition};
+///
+/// let mouse: MouseInput = "LeftPress@12,4".parse()?;
+/// assert_eq!(mouse.event, MouseEvent::LeftPress);
+/// assert_eq!(mouse.position, TerminalPosition::row_col(4, 12));
+/// assert_eq!(mouse.to_string(), "LeftPress@12,4");
+/// # Ok::<(), String>(())
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MouseInput {
     /// The type of mouse event that occurred.
@@ -79,6 +142,12 @@ pub struct MouseInput {
 
     /// Indicates whether the Shift modifier key was pressed during the event.
     pub shift: bool,
+
+    /// The position in pixels where the mouse event occurred.
+    ///
+    /// This is only reported in SGR-pixels mode
+    /// (see [`Terminal::set_pixel_mouse_input()`](crate::Terminal::set_pixel_mouse_input)).
+    pub pixel_position: Option<PixelPosition>,
 }
 
 /// Mouse event types.
@@ -97,26 +166,849 @@ pub enum MouseEvent {
     /// Middle mouse button released.
     MiddleRelease,
     /// Mouse moved while a button is held down (drag).
-    Drag,
+    Drag(MouseButton),
+    /// Mouse moved with no button held down.
+    ///
+    /// This is only reported with [`MouseTracking::AnyMotion`](crate::MouseTracking::AnyMotion).
+    Move,
     /// Mouse wheel scrolled up.
     ScrollUp,
     /// Mouse wheel scrolled down.
     ScrollDown,
+    /// Mouse wheel scrolled left (horizontal scrolling).
+    ScrollLeft,
+    /// Mouse wheel scrolled right (horizontal scrolling).
+    ScrollRight,
+    /// Extra mouse button pressed, identified by its X11 button number
+    /// (e.g., 8 for "back" and 9 for "forward").
+    ExtraPress(u8),
+    /// Extra mouse button released, identified by its X11 button number.
+    ExtraRelease(u8),
+}
+
+/// Mouse button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MouseButton {
+    /// Left mouse button.
+    Left,
+    /// Middle mouse button.
+    Middle,
+    /// Right mouse button.
+    Right,
+    /// Extra mouse button, identified by its X11 button number (8 to 11).
+    Extra(u8),
+}
+
+impl Display for TerminalInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Key(input) => write!(f, "{input}"),
+            Self::Mouse(input) => write!(f, "{input}"),
+        }
+    }
+}
+
+impl FromStr for TerminalInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(input) = s.parse() {
+            return Ok(Self::Key(input));
+        }
+        s.parse()
+            .map(Self::Mouse)
+            .map_err(|_| format!("invalid terminal input: {s:?}"))
+    }
+}
+
+impl Display for KeyInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        write!(f, "{}", self.code)
+    }
+}
+
+impl FromStr for KeyInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid key input: {s:?}");
+        let (modifiers, code) = strip_modifiers(s);
+        let code = match (modifiers.shift, code.parse().map_err(|_| error())?) {
+            (true, KeyCode::Tab) => KeyCode::BackTab,
+            (true, _) => return Err(error()),
+            (false, code) => code,
+        };
+        Ok(Self {
+            ctrl: modifiers.ctrl,
+            alt: modifiers.alt,
+            code,
+        })
+    }
+}
+
+impl Display for KeyCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Enter => write!(f, "Enter"),
+            Self::Escape => write!(f, "Escape"),
+            Self::Backspace => write!(f, "Backspace"),
+            Self::Tab => write!(f, "Tab"),
+            Self::BackTab => write!(f, "BackTab"),
+            Self::Delete => write!(f, "Delete"),
+            Self::Insert => write!(f, "Insert"),
+            Self::Up => write!(f, "Up"),
+            Self::Down => write!(f, "Down"),
+            Self::Left => write!(f, "Left"),
+            Self::Right => write!(f, "Right"),
+            Self::Home => write!(f, "Home"),
+            Self::End => write!(f, "End"),
+            Self::PageUp => write!(f, "PageUp"),
+            Self::PageDown => write!(f, "PageDown"),
+            Self::F(n) => write!(f, "F{n}"),
+            Self::Keypad(c) => write!(f, "Keypad{c}"),
+            Self::KeypadEnter => write!(f, "KeypadEnter"),
+            Self::Char(' ') => write!(f, "Space"),
+            Self::Char(c) if c.is_control() || c.is_whitespace() => {
+                write!(f, "U+{:04X}", *c as u32)
+            }
+            Self::Char(c) => write!(f, "{c}"),
+        }
+    }
+}
+
+impl FromStr for KeyCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid key code: {s:?}");
+
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Self::Char(c));
+        }
+
+        let code = match s.to_ascii_lowercase().as_str() {
+            "enter" | "return" | "ret" | "cr" => Self::Enter,
+            "escape" | "esc" => Self::Escape,
+            "backspace" | "bs" => Self::Backspace,
+            "tab" => Self::Tab,
+            "backtab" | "s-tab" => Self::BackTab,
+            "delete" | "del" => Self::Delete,
+            "insert" | "ins" => Self::Insert,
+            "up" => Self::Up,
+            "down" => Self::Down,
+            "left" => Self::Left,
+            "right" => Self::Right,
+            "home" => Self::Home,
+            "end" => Self::End,
+            "pageup" | "pgup" => Self::PageUp,
+            "pagedown" | "pgdn" => Self::PageDown,
+            "space" | "spc" => Self::Char(' '),
+            "keypadenter" => Self::KeypadEnter,
+            name => {
+                if name.starts_with("keypad") {
+                    let mut chars = s["keypad".len()..].chars();
+                    let (Some(c), None) = (chars.next(), chars.next()) else {
+                        return Err(error());
+                    };
+                    Self::Keypad(c)
+                } else if let Some(n) = name.strip_prefix('f') {
+                    match n.parse().map_err(|_| error())? {
+                        0 => return Err(error()),
+                        n => Self::F(n),
+                    }
+                } else if let Some(hex) = name.strip_prefix("u+") {
+                    u32::from_str_radix(hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .map(Self::Char)
+                        .ok_or_else(error)?
+                } else {
+                    return Err(error());
+                }
+            }
+        };
+        Ok(code)
+    }
+}
+
+impl Display for MouseInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        write!(
+            f,
+            "{}@{},{}",
+            self.event, self.position.col, self.position.row
+        )?;
+        if let Some(p) = self.pixel_position {
+            write!(f, "/{},{}", p.x, p.y)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for MouseInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid mouse input: {s:?}");
+        let parse_xy = |xy: &str| -> Option<(usize, usize)> {
+            let (x, y) = xy.split_once(',')?;
+            Some((x.parse().ok()?, y.parse().ok()?))
+        };
+
+        let (modifiers, s0) = strip_modifiers(s);
+        let (event, s0) = s0.split_once('@').ok_or_else(error)?;
+        let (cell, pixel) = match s0.split_once('/') {
+            Some((cell, pixel)) => (cell, Some(pixel)),
+            None => (s0, None),
+        };
+        let (col, row) = parse_xy(cell).ok_or_else(error)?;
+        let pixel_position = pixel
+            .map(|pixel| parse_xy(pixel).map(|(x, y)| PixelPosition::xy(x, y)))
+            .map(|p| p.ok_or_else(error))
+            .transpose()?;
+        Ok(Self {
+            event: event.parse().map_err(|_| error())?,
+            position: TerminalPosition::row_col(row, col),
+            ctrl: modifiers.ctrl,
+            alt: modifiers.alt,
+            shift: modifiers.shift,
+            pixel_position,
+        })
+    }
+}
+
+impl Display for MouseEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LeftPress => write!(f, "LeftPress"),
+            Self::LeftRelease => write!(f, "LeftRelease"),
+            Self::RightPress => write!(f, "RightPress"),
+            Self::RightRelease => write!(f, "RightRelease"),
+            Self::MiddlePress => write!(f, "MiddlePress"),
+            Self::MiddleRelease => write!(f, "MiddleRelease"),
+            Self::Drag(button) => write!(f, "{button}Drag"),
+            Self::Move => write!(f, "Move"),
+            Self::ScrollUp => write!(f, "ScrollUp"),
+            Self::ScrollDown => write!(f, "ScrollDown"),
+            Self::ScrollLeft => write!(f, "ScrollLeft"),
+            Self::ScrollRight => write!(f, "ScrollRight"),
+            Self::ExtraPress(n) => write!(f, "{}Press", MouseButton::Extra(*n)),
+            Self::ExtraRelease(n) => write!(f, "{}Release", MouseButton::Extra(*n)),
+        }
+    }
+}
+
+impl FromStr for MouseEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid mouse event: {s:?}");
+
+        let name = s.to_ascii_lowercase();
+        let event = match name.as_str() {
+            "move" => Self::Move,
+            "scrollup" => Self::ScrollUp,
+            "scrolldown" => Self::ScrollDown,
+            "scrollleft" => Self::ScrollLeft,
+            "scrollright" => Self::ScrollRight,
+            _ => {
+                let parse_button = |len: usize| s[..s.len() - len].parse::<MouseButton>();
+                if name.ends_with("press") {
+                    match parse_button("press".len())? {
+                        MouseButton::Left => Self::LeftPress,
+                        MouseButton::Middle => Self::MiddlePress,
+                        MouseButton::Right => Self::RightPress,
+                        MouseButton::Extra(n) => Self::ExtraPress(n),
+                    }
+                } else if name.ends_with("release") {
+                    match parse_button("release".len())? {
+                        MouseButton::Left => Self::LeftRelease,
+                        MouseButton::Middle => Self::MiddleRelease,
+                        MouseButton::Right => Self::RightRelease,
+                        MouseButton::Extra(n) => Self::ExtraRelease(n),
+                    }
+                } else if name.ends_with("drag") {
+                    Self::Drag(parse_button("drag".len())?)
+                } else {
+                    return Err(error());
+                }
+            }
+        };
+        Ok(event)
+    }
+}
+
+impl Display for MouseButton {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Left => write!(f, "Left"),
+            Self::Middle => write!(f, "Middle"),
+            Self::Right => write!(f, "Right"),
+            Self::Extra(n) => write!(f, "Button{n}"),
+        }
+    }
+}
+
+impl FromStr for MouseButton {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid mouse button: {s:?}");
+
+        let name = s.to_ascii_lowercase();
+        let button = match name.as_str() {
+            "left" => Self::Left,
+            "middle" => Self::Middle,
+            "right" => Self::Right,
+            _ => {
+                let n = name.strip_prefix("button").ok_or_else(error)?;
+                Self::Extra(n.parse().map_err(|_| error())?)
+            }
+        };
+        Ok(button)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+/// Strips leading modifiers (e.g., `Ctrl+` or `C-`) from `s`, matched case-insensitively.
+///
+/// A modifier is not stripped if nothing follows it, so `C-` alone is left as it is.
+pub(crate) fn strip_modifiers(mut s: &str) -> (Modifiers, &str) {
+    const PREFIXES: [&str; 7] = ["Ctrl+", "C-", "Alt+", "M-", "A-", "Shift+", "S-"];
+
+    let mut modifiers = Modifiers::default();
+    while let Some(prefix) = PREFIXES.into_iter().find(|prefix| {
+        s.len() > prefix.len()
+            && s.get(..prefix.len())
+                .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+    }) {
+        match prefix {
+            "Ctrl+" | "C-" => modifiers.ctrl = true,
+            "Alt+" | "M-" | "A-" => modifiers.alt = true,
+            _ => modifiers.shift = true,
+        }
+        s = &s[prefix.len()..];
+    }
+    (modifiers, s)
+}
+
+/// Reply to a query sent to the terminal, which is kept out of [`TerminalInput`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TerminalResponse {
+    /// Operating System Command reply (without the leading `ESC ]` and the terminator).
+    Osc(String),
+
+    /// Extended Cursor Position Report (reply to DECXCPR, `CSI ? 6 n`).
+    CursorPosition(TerminalPosition),
+
+    /// Size of a character cell in pixels (reply to `CSI 16 t`).
+    CellPixelSize(PixelSize),
+}
+
+/// Default maximum length of a single input sequence.
+const DEFAULT_MAX_SEQUENCE_LEN: usize = 1024 * 1024;
+
+/// Minimum value accepted by [`InputParser::set_max_sequence_len()`].
+const MIN_MAX_SEQUENCE_LEN: usize = 64;
+
+/// Kind of an overlong sequence whose remaining bytes are being discarded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Discarding {
+    /// OSC, DCS and other string sequences, terminated by BEL or ST (`ESC \`).
+    String,
+    /// CSI sequences, terminated by a byte in the range 0x40..=0x7e.
+    Csi,
+}
+
+/// Family of key encodings used by a terminal.
+///
+/// [`InputParser`] always decodes the xterm encodings, and additionally decodes the
+/// sequences specific to the selected dialect. The dialect of the current terminal can be
+/// guessed from its terminfo entry or its name by [`InputDialect::from_env()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputDialect {
+    /// xterm and compatible terminals.
+    #[default]
+    Xterm,
+
+    /// rxvt and urxvt.
+    ///
+    /// Shifted and Ctrl cursor keys (`ESC [ a`, `ESC O a`), editing and function keys with
+    /// modifier suffixes (`ESC [ 2 $`, `ESC [ 5 ^`, `ESC [ 11 @`), and escape sequences
+    /// prefixed by ESC for Alt (`ESC ESC [ A`) are decoded.
+    Rxvt,
+
+    /// Linux console.
+    ///
+    /// F1 to F5 (`ESC [ [ A` to `ESC [ [ E`) are decoded.
+    Linux,
+}
+
+impl InputDialect {
+    /// Guesses the dialect from a terminal type name, such as `"rxvt-unicode-256color"`.
+    ///
+    /// Unknown terminal types are assumed to be xterm-compatible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tuinix::InputDialect;
+    ///
+    /// assert_eq!(InputDialect::from_term("rxvt-unicode-256color"), InputDialect::Rxvt);
+    /// assert_eq!(InputDialect::from_term("linux"), InputDialect::Linux);
+    /// assert_eq!(InputDialect::from_term("xterm-256color"), InputDialect::Xterm);
+    /// ```
+    pub fn from_term(term: &str) -> Self {
+        if term.starts_with("rxvt") {
+            Self::Rxvt
+        } else if term == "linux" || term.starts_with("linux-") {
+            Self::Linux
+        } else {
+            Self::Xterm
+        }
+    }
+
+    /// Guesses the dialect from the terminfo entry of a terminal type.
+    ///
+    /// The entry is looked up in the standard locations (`$TERMINFO`, `~/.terminfo`,
+    /// `$TERMINFO_DIRS`, `/etc/terminfo`, `/lib/terminfo` and `/usr/share/terminfo`),
+    /// and the dialect is told from the key sequences it defines: F1 as `ESC [ [ A`
+    /// for [`InputDialect::Linux`], and Shift+Home or Shift+Delete ending with `$`
+    /// for [`InputDialect::Rxvt`].
+    ///
+    /// Returns `None` if no valid entry is found.
+    pub fn from_terminfo(term: &str) -> Option<Self> {
+        Self::from_terminfo_entry(&crate::terminfo::load(term)?)
+    }
+
+    pub(crate) fn from_terminfo_entry(entry: &[u8]) -> Option<Self> {
+        use crate::terminfo::{KEY_F1, KEY_SDC, KEY_SHOME, string_capabilities};
+
+        let strings = string_capabilities(entry)?;
+        let get = |i: usize| strings.get(i).copied().flatten();
+        if get(KEY_F1) == Some(b"\x1b[[A") {
+            Some(Self::Linux)
+        } else if [KEY_SHOME, KEY_SDC]
+            .into_iter()
+            .any(|i| get(i).is_some_and(|s| s.ends_with(b"$")))
+        {
+            Some(Self::Rxvt)
+        } else {
+            Some(Self::Xterm)
+        }
+    }
+
+    /// Guesses the dialect of the terminal named by the `TERM` environment variable.
+    ///
+    /// The terminfo entry is used if available (see [`InputDialect::from_terminfo()`]),
+    /// and the name otherwise (see [`InputDialect::from_term()`]).
+    pub fn from_env() -> Self {
+        std::env::var("TERM")
+            .map(|term| Self::from_terminfo(&term).unwrap_or_else(|| Self::from_term(&term)))
+            .unwrap_or_default()
+    }
+}
+
+/// Push-based decoder of terminal input bytes.
+///
+/// Unlike [`Terminal::read_input()`](crate::Terminal::read_input), this does not perform
+/// any I/O by itself, so it can decode input received through other channels,
+/// such as an SSH channel or a pty master. Bytes are given by [`InputParser::feed()`]
+/// in chunks of any size, and decoded inputs are taken out by [`InputParser::next_input()`]
+/// or by iterating over the parser.
+///
+/// # Examples
+///
+/// ```
+/// use tuinix::{InputParser, KeyCode, TerminalInput};
+///
+/// let mut parser = InputParser::new();
+/// parser.feed(b"a\x1b[");
+/// let inputs: Vec<_> = parser.by_ref().collect();
+/// assert_eq!(inputs.len(), 1);
+/// assert_eq!(parser.remainder(), b"\x1b[");
+///
+/// // The rest of the sequence arrives later
+/// parser.feed(b"A");
+/// let Some(TerminalInput::Key(key)) = parser.next_input() else {
+///     panic!();
+/// };
+/// assert_eq!(key.code, KeyCode::Up);
+/// assert!(parser.remainder().is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct InputParser {
+    buf: Vec<u8>,
+    buf_start: usize,
+    max_sequence_len: usize,
+    discarding: Option<Discarding>,
+    queued: VecDeque<(TerminalInput, Instant)>,
+    fed_len: u64,
+    chunk_times: VecDeque<(u64, Instant)>,
+    responses: Vec<TerminalResponse>,
+    awaiting_response: bool,
+    pixel_mouse: bool,
+    utf8_mouse: bool,
+    cell_pixel_size: PixelSize,
+    distinct_keypad_keys: bool,
+    linefeed_as_enter: bool,
+    dialect: InputDialect,
+}
+
+impl InputParser {
+    /// Makes a new [`InputParser`] instance.
+    pub fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            buf_start: 0,
+            max_sequence_len: DEFAULT_MAX_SEQUENCE_LEN,
+            discarding: None,
+            queued: VecDeque::new(),
+            fed_len: 0,
+            chunk_times: VecDeque::new(),
+            responses: Vec::new(),
+            awaiting_response: false,
+            pixel_mouse: false,
+            utf8_mouse: false,
+            cell_pixel_size: PixelSize::EMPTY,
+            distinct_keypad_keys: false,
+            linefeed_as_enter: false,
+            dialect: InputDialect::Xterm,
+        }
+    }
+
+    /// Sets the maximum length in bytes of a single input sequence (1 MiB by default).
+    ///
+    /// An incomplete sequence exceeding the limit is discarded along with its remaining bytes,
+    /// and decoding resumes with the next input. Limits below 64 bytes are raised to 64.
+    pub fn set_max_sequence_len(&mut self, len: usize) {
+        self.max_sequence_len = len.max(MIN_MAX_SEQUENCE_LEN);
+    }
+
+    /// Sets whether keypad keys sent in the application keypad mode are reported as
+    /// [`KeyCode::Keypad`] and [`KeyCode::KeypadEnter`] (disabled by default).
+    ///
+    /// If disabled, they are reported as the corresponding main keys
+    /// (e.g., [`KeyCode::Char('5')`](KeyCode::Char) and [`KeyCode::Enter`]).
+    pub fn set_distinct_keypad_keys(&mut self, enabled: bool) {
+        self.distinct_keypad_keys = enabled;
+    }
+
+    /// Sets whether LF (0x0a) is reported as [`KeyCode::Enter`] rather than Ctrl+J
+    /// (disabled by default).
+    ///
+    /// Some terminals send LF for (modified) Enter, as does piped input for line ends.
+    /// `ESC LF` is then reported as Alt+Enter.
+    pub fn set_linefeed_as_enter(&mut self, enabled: bool) {
+        self.linefeed_as_enter = enabled;
+    }
+
+    /// Sets the dialect of the key sequences to be decoded ([`InputDialect::Xterm`] by default).
+    pub fn set_dialect(&mut self, dialect: InputDialect) {
+        self.dialect = dialect;
+    }
+
+    /// Returns the dialect of the key sequences to be decoded.
+    pub fn dialect(&self) -> InputDialect {
+        self.dialect
+    }
+
+    /// Appends bytes to be decoded, received at the current time.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.feed_at(bytes, Instant::now());
+    }
+
+    /// Appends bytes to be decoded, received at `time`.
+    ///
+    /// Each decoded input is timestamped with the time at which its last byte was fed
+    /// (see [`InputParser::next_timed_input()`]). This is mainly useful for replaying
+    /// recorded input or for testing time-dependent logic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use tuinix::InputParser;
+    ///
+    /// let t0 = Instant::now();
+    /// let t1 = t0 + Duration::from_millis(10);
+    ///
+    /// let mut parser = InputParser::new();
+    /// parser.feed_at(b"a\x1b[", t0);
+    /// parser.feed_at(b"A", t1);
+    /// assert_eq!(parser.next_timed_input().map(|(_, time)| time), Some(t0));
+    /// assert_eq!(parser.next_timed_input().map(|(_, time)| time), Some(t1));
+    /// ```
+    pub fn feed_at(&mut self, bytes: &[u8], time: Instant) {
+        if bytes.is_empty() {
+            return;
+        }
+        let decoded_len = self.decoded_len();
+        while self
+            .chunk_times
+            .front()
+            .is_some_and(|&(end, _)| end <= decoded_len)
+        {
+            self.chunk_times.pop_front();
+        }
+        self.fed_len += bytes.len() as u64;
+        self.chunk_times.push_back((self.fed_len, time));
+
+        if self.buf_start > 0 {
+            self.buf.drain(..self.buf_start);
+            self.buf_start = 0;
+        }
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Decodes and returns the next input, or `None` if more bytes are needed.
+    pub fn next_input(&mut self) -> Option<TerminalInput> {
+        self.next_timed_input().map(|(input, _)| input)
+    }
+
+    /// Like [`InputParser::next_input()`], but also returns the time at which
+    /// the last byte of the input was fed.
+    pub fn next_timed_input(&mut self) -> Option<(TerminalInput, Instant)> {
+        if let Some(input) = self.queued.pop_front() {
+            return Some(input);
+        }
+        self.decode_input()
+    }
+
+    /// Returns the total number of fed bytes that have been decoded (or skipped).
+    fn decoded_len(&self) -> u64 {
+        self.fed_len - self.remainder().len() as u64
+    }
+
+    /// Returns the time at which the last decoded byte was fed.
+    fn last_decoded_time(&self) -> Instant {
+        let decoded_len = self.decoded_len();
+        self.chunk_times
+            .iter()
+            .find(|&&(end, _)| end >= decoded_len)
+            .or(self.chunk_times.back())
+            .map_or_else(Instant::now, |&(_, time)| time)
+    }
+
+    /// Returns the bytes that have been fed but not decoded yet,
+    /// such as an incomplete escape sequence.
+    pub fn remainder(&self) -> &[u8] {
+        &self.buf[self.buf_start..]
+    }
+
+    fn decode_input(&mut self) -> Option<(TerminalInput, Instant)> {
+        loop {
+            self.skip_discarded_bytes();
+            if self.discarding.is_some() {
+                return None;
+            }
+
+            let bytes = self.remainder();
+            let (input, response, consumed_size) =
+                if let Some((response, size)) = parse_response(bytes, self.awaiting_response) {
+                    (None, response, size)
+                } else if let Some((input, size)) = parse_dialect_input(bytes, self.dialect) {
+                    (input, None, size)
+                } else if let Some((input, size)) =
+                    parse_linefeed(bytes).filter(|_| self.linefeed_as_enter)
+                {
+                    (Some(input), None, size)
+                } else if self.utf8_mouse && bytes.starts_with(b"\x1b[M") {
+                    let (input, size) = parse_x10_mouse_sequence(bytes, true).unwrap_or((None, 1));
+                    (input, None, size)
+                } else {
+                    // Malformed bytes are skipped one by one
+                    let (input, size) = parse_input(bytes).unwrap_or((None, 1));
+                    (input, None, size)
+                };
+            self.buf_start += consumed_size;
+            if consumed_size > self.max_sequence_len {
+                // Overlong sequences are dropped even if they arrive at once
+                continue;
+            }
+            if let Some(response) = response
+                && self.awaiting_response
+            {
+                self.responses.push(response);
+            }
+            if let Some(input) = input {
+                return Some((self.convert_input(input), self.last_decoded_time()));
+            }
+            if consumed_size > 0 {
+                continue;
+            }
+
+            if self.remainder().len() > self.max_sequence_len {
+                self.discard_overlong_sequence();
+                continue;
+            }
+            if self.remainder().is_empty() {
+                self.buf.clear();
+                self.buf_start = 0;
+            }
+            return None;
+        }
+    }
+
+    /// Drops an incomplete sequence that has exceeded the length limit,
+    /// and skips the rest of it as it arrives.
+    fn discard_overlong_sequence(&mut self) {
+        self.discarding = match self.remainder().get(1) {
+            Some(b'[') => Some(Discarding::Csi),
+            _ => Some(Discarding::String),
+        };
+
+        // Skip the introducer, and the rest in `skip_discarded_bytes()`,
+        // which keeps any inputs following the sequence in the buffer
+        self.buf_start += self.remainder().len().min(2);
+    }
+
+    /// Skips the bytes of a discarded sequence at the beginning of the buffer.
+    fn skip_discarded_bytes(&mut self) {
+        let Some(discarding) = self.discarding else {
+            return;
+        };
+
+        let bytes = self.remainder();
+        let mut skipped = bytes.len();
+        for (i, &b) in bytes.iter().enumerate() {
+            let end = match (discarding, b) {
+                (Discarding::String, 0x07) | (Discarding::Csi, 0x40..=0x7e) => i + 1,
+                (Discarding::String, 0x1b) if bytes.get(i + 1) == Some(&b'\\') => i + 2,
+                (Discarding::String, 0x1b) if i + 1 == bytes.len() => {
+                    // Need more bytes to tell whether this is ST
+                    skipped = i;
+                    break;
+                }
+                // A new sequence begins
+                (_, 0x1b) | (Discarding::Csi, ..0x20) => i,
+                _ => continue,
+            };
+            skipped = end;
+            self.discarding = None;
+            break;
+        }
+        self.buf_start += skipped;
+    }
+
+    /// In SGR-pixels mode, mouse reports carry pixel coordinates instead of cell ones.
+    fn convert_input(&self, input: TerminalInput) -> TerminalInput {
+        match input {
+            TerminalInput::Key(mut key) if !self.distinct_keypad_keys => {
+                key.code = match key.code {
+                    KeyCode::Keypad(c) => KeyCode::Char(c),
+                    KeyCode::KeypadEnter => KeyCode::Enter,
+                    code => code,
+                };
+                TerminalInput::Key(key)
+            }
+            TerminalInput::Mouse(mut mouse) if self.pixel_mouse => {
+                let pixel_position = PixelPosition::xy(mouse.position.col, mouse.position.row);
+                mouse.position = pixel_position.to_cell_position(self.cell_pixel_size);
+                mouse.pixel_position = Some(pixel_position);
+                TerminalInput::Mouse(mouse)
+            }
+            input => input,
+        }
+    }
+
+    /// Starts or stops interpreting mouse reports as SGR-pixels (mode 1016) reports.
+    pub(crate) fn set_pixel_mouse(&mut self, enabled: bool) {
+        self.pixel_mouse = enabled;
+    }
+
+    /// Starts or stops decoding X10 mouse reports in the UTF-8 encoding (mode 1005).
+    pub(crate) fn set_utf8_mouse(&mut self, enabled: bool) {
+        self.utf8_mouse = enabled;
+    }
+
+    /// Sets the cell size used to derive cell positions from pixel positions.
+    pub(crate) fn set_cell_pixel_size(&mut self, size: PixelSize) {
+        self.cell_pixel_size = size;
+    }
+
+    /// Starts or stops treating ambiguous bytes as the beginning of a query reply.
+    ///
+    /// Replies received while not awaiting one are discarded.
+    pub(crate) fn set_awaiting_response(&mut self, awaiting: bool) {
+        self.awaiting_response = awaiting;
+        self.responses.clear();
+    }
+
+    /// Decodes all complete inputs in the buffer and queues them,
+    /// so that any replies behind them are collected.
+    pub(crate) fn queue_inputs(&mut self) {
+        while let Some(input) = self.decode_input() {
+            self.queued.push_back(input);
+        }
+    }
+
+    /// Removes and returns the first received reply accepted by `f`.
+    pub(crate) fn take_response<T, F>(&mut self, mut f: F) -> Option<T>
+    where
+        F: FnMut(&TerminalResponse) -> Option<T>,
+    {
+        let (i, value) = self
+            .responses
+            .iter()
+            .enumerate()
+            .find_map(|(i, r)| f(r).map(|v| (i, v)))?;
+        self.responses.remove(i);
+        Some(value)
+    }
+}
+
+impl Default for InputParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for InputParser {
+    type Item = TerminalInput;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_input()
+    }
 }
 
 #[derive(Debug)]
 pub struct InputReader<R> {
     inner: R,
-    buf: Vec<u8>,
-    buf_offset: usize,
+    parser: InputParser,
+    read_buf: Vec<u8>,
+    empty_read_allowed: bool,
 }
 
 impl<R: Read> InputReader<R> {
     pub fn new(inner: R) -> Self {
         Self {
             inner,
-            buf: vec![0; 64],
-            buf_offset: 0,
+            parser: InputParser::new(),
+            read_buf: vec![0; 1024],
+            empty_read_allowed: false,
         }
     }
 
@@ -124,32 +1016,195 @@ impl<R: Read> InputReader<R> {
         &self.inner
     }
 
+    pub fn parser_mut(&mut self) -> &mut InputParser {
+        &mut self.parser
+    }
+
     pub fn read_input(&mut self) -> std::io::Result<Option<TerminalInput>> {
-        if self.buf_offset > 0
-            && let Some(input) = self.read_input_from_buf()?
-        {
+        if let Some(input) = self.parser.next_input() {
             return Ok(Some(input));
         }
 
-        let read_size = self.inner.read(&mut self.buf[self.buf_offset..])?;
-        if read_size == 0 {
+        self.fill_buf()?;
+        Ok(self.parser.next_input())
+    }
+
+    /// Reads available bytes from the inner reader and feeds them to the parser.
+    pub fn fill_buf(&mut self) -> std::io::Result<()> {
+        self.read_and_feed(self.empty_read_allowed)
+    }
+
+    /// Like [`InputReader::fill_buf()`], but for when the inner reader has been reported
+    /// readable, in which case a read returning no bytes is always EOF (e.g., a hangup).
+    pub fn fill_ready_buf(&mut self) -> std::io::Result<()> {
+        self.read_and_feed(false)
+    }
+
+    fn read_and_feed(&mut self, empty_read_allowed: bool) -> std::io::Result<()> {
+        let read_size = self.inner.read(&mut self.read_buf)?;
+        if read_size == 0 && !empty_read_allowed {
             return Err(std::io::ErrorKind::UnexpectedEof.into());
         }
 
-        self.buf_offset += read_size;
-        self.read_input_from_buf()
+        self.parser.feed(&self.read_buf[..read_size]);
+        Ok(())
     }
 
-    pub(crate) fn read_input_from_buf(&mut self) -> std::io::Result<Option<TerminalInput>> {
-        loop {
-            let (input, consumed_size) = parse_input(&self.buf[..self.buf_offset])?;
-            self.buf.copy_within(consumed_size..self.buf_offset, 0);
-            self.buf_offset -= consumed_size;
-            if input.is_none() && consumed_size > 0 {
-                continue;
+    /// Sets whether a read returning no bytes is a timeout (e.g., with `VMIN=0`) rather than EOF.
+    pub fn set_empty_read_allowed(&mut self, allowed: bool) {
+        self.empty_read_allowed = allowed;
+    }
+}
+
+/// Parses a query reply at the beginning of `bytes`.
+///
+/// Returns `None` if `bytes` does not start with a reply, and `Some((None, 0))`
+/// if more bytes are needed to complete it.
+fn parse_response(
+    bytes: &[u8],
+    awaiting_response: bool,
+) -> Option<(Option<TerminalResponse>, usize)> {
+    if bytes.starts_with(b"\x1b[") {
+        return if awaiting_response {
+            parse_csi_response(bytes)
+        } else {
+            None
+        };
+    }
+    if !bytes.starts_with(b"\x1b]") {
+        return None;
+    }
+
+    // `ESC ]` alone is also sent by Alt+], so it is only treated as
+    // a reply prefix while a reply is expected.
+    match bytes.get(2) {
+        None if awaiting_response => return Some((None, 0)),
+        Some(b) if b.is_ascii_digit() => {}
+        _ => return None,
+    }
+
+    // OSC replies are terminated by BEL or ST (ESC \)
+    for (i, &b) in bytes.iter().enumerate().skip(2) {
+        let end = match b {
+            0x07 => i + 1,
+            0x1b if bytes.get(i + 1) == Some(&b'\\') => i + 2,
+            0x1b if i + 1 == bytes.len() => return Some((None, 0)),
+            _ => continue,
+        };
+        let body = String::from_utf8_lossy(&bytes[2..i]).into_owned();
+        return Some((Some(TerminalResponse::Osc(body)), end));
+    }
+    Some((None, 0))
+}
+
+fn parse_csi_response(bytes: &[u8]) -> Option<(Option<TerminalResponse>, usize)> {
+    let Some((params, final_byte, size)) = split_csi_sequence(bytes) else {
+        return Some((None, 0));
+    };
+    let response = match (final_byte, params) {
+        // The DECXCPR form (`ESC [ ? r ; c R`), whose optional third parameter is the page number
+        (b'R', [b'?', params @ ..]) => match parse_csi_params(params)?.as_slice() {
+            &[row, col] | &[row, col, _] => cursor_position_response(row, col),
+            _ => return None,
+        },
+        // The plain form (`ESC [ r ; c R`), for terminals that reply to DECXCPR with it.
+        // Row 1 with a column from 2 to 16 is left as a modified F3 key (e.g., `ESC [ 1 ; 2 R`),
+        // as the two cannot be told apart
+        (b'R', _) => match *parse_csi_params(params)?.as_slice() {
+            [1, 2..=16] => return None,
+            [row, col] => cursor_position_response(row, col),
+            _ => return None,
+        },
+        _ => match (final_byte, parse_csi_params(params)?.as_slice()) {
+            (b't', &[6, height, width]) => TerminalResponse::CellPixelSize(
+                PixelSize::width_height(width as usize, height as usize),
+            ),
+            _ => return None,
+        },
+    };
+    Some((Some(response), size))
+}
+
+fn cursor_position_response(row: u16, col: u16) -> TerminalResponse {
+    TerminalResponse::CursorPosition(TerminalPosition::row_col(
+        row.saturating_sub(1) as usize,
+        col.saturating_sub(1) as usize,
+    ))
+}
+
+/// Parses a key sequence specific to `dialect`.
+///
+/// Returns `None` if the bytes should be parsed by [`parse_input()`] instead.
+fn parse_dialect_input(
+    bytes: &[u8],
+    dialect: InputDialect,
+) -> Option<(Option<TerminalInput>, usize)> {
+    match dialect {
+        InputDialect::Xterm => None,
+        InputDialect::Rxvt => parse_rxvt_input(bytes),
+        InputDialect::Linux => parse_linux_input(bytes),
+    }
+}
+
+fn parse_rxvt_input(bytes: &[u8]) -> Option<(Option<TerminalInput>, usize)> {
+    let arrow =
+        |b: u8| [KeyCode::Up, KeyCode::Down, KeyCode::Right, KeyCode::Left][usize::from(b - b'a')];
+    match bytes {
+        // Alt + key sequence (`ESC ESC` alone is left to be parsed as Escape)
+        [0x1b, 0x1b, b'[' | b'O', ..] => {
+            let (input, size) =
+                parse_rxvt_input(&bytes[1..]).or_else(|| parse_input(&bytes[1..]).ok())?;
+            if size == 0 {
+                return Some((None, 0)); // Need more bytes
+            }
+            // Other sequences are left to be parsed as a standalone ESC followed by the sequence
+            let Some(TerminalInput::Key(key)) = input else {
+                return None;
+            };
+            Some((Some(create_key_input(key.ctrl, true, key.code)), size + 1))
+        }
+        // Shift + cursor keys (Shift is not reported, as [`KeyInput`] has no Shift flag)
+        [0x1b, b'[', b @ b'a'..=b'd', ..] => {
+            Some((Some(create_key_input(false, false, arrow(*b))), 3))
+        }
+        // Ctrl + cursor keys
+        [0x1b, b'O', b @ b'a'..=b'd', ..] => {
+            Some((Some(create_key_input(true, false, arrow(*b))), 3))
+        }
+        // Editing and function keys with a modifier suffix:
+        // `$` (Shift), `^` (Ctrl) or `@` (Ctrl+Shift) instead of `~`
+        [0x1b, b'[', rest @ ..] => {
+            let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+            if digits == 0 {
+                return None;
             }
-            return Ok(input);
+            let Some(&suffix) = rest.get(digits) else {
+                return Some((None, 0)); // Need more bytes
+            };
+            let modifier = match suffix {
+                b'$' => 2,
+                b'^' => 5,
+                b'@' => 6,
+                _ => return None,
+            };
+            let key = std::str::from_utf8(&rest[..digits]).ok()?.parse().ok();
+            let input = key.and_then(|key| parse_csi_key(&[key, modifier], b'~'));
+            Some((input, digits + 3))
         }
+        _ => None,
+    }
+}
+
+fn parse_linux_input(bytes: &[u8]) -> Option<(Option<TerminalInput>, usize)> {
+    match bytes {
+        [0x1b, b'[', b'['] => Some((None, 0)), // Need more bytes
+        // F1 through F5
+        [0x1b, b'[', b'[', b, ..] => {
+            let input = matches!(b, b'A'..=b'E')
+                .then(|| create_key_input(false, false, KeyCode::F(b - b'A' + 1)));
+            Some((input, 4))
+        }
+        _ => None,
     }
 }
 
@@ -175,13 +1230,9 @@ fn parse_input(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)>
 fn parse_ascii_char(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)> {
     let byte = bytes[0];
 
-    // Control characters (Ctrl+A through Ctrl+Z)
+    // Control characters (e.g., Ctrl+A)
     if byte < 0x20 {
-        let (ctrl, code) = match byte {
-            0x0D => (false, KeyCode::Enter), // Enter
-            0x09 => (false, KeyCode::Tab),   // Tab
-            c => (true, KeyCode::Char((c + 0x60) as char)),
-        };
+        let (ctrl, code) = decode_control_char(byte);
         return Ok((Some(create_key_input(ctrl, false, code)), 1));
     }
 
@@ -192,6 +1243,33 @@ fn parse_ascii_char(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usi
     ))
 }
 
+/// Decodes LF (0x0a), optionally preceded by ESC, as (Alt+)Enter.
+fn parse_linefeed(bytes: &[u8]) -> Option<(TerminalInput, usize)> {
+    let (alt, size) = match bytes {
+        [b'\n', ..] => (false, 1),
+        [0x1b, b'\n', ..] => (true, 2),
+        _ => return None,
+    };
+    Some((create_key_input(false, alt, KeyCode::Enter), size))
+}
+
+/// Decodes a C0 control character (other than ESC) into `(ctrl, code)`.
+///
+/// Note that LF (0x0a) is decoded as Ctrl+J, unless [`InputParser::set_linefeed_as_enter()`]
+/// is enabled.
+fn decode_control_char(byte: u8) -> (bool, KeyCode) {
+    match byte {
+        0x0d => (false, KeyCode::Enter),
+        0x09 => (false, KeyCode::Tab),
+        // Ctrl+Space (and Ctrl+@)
+        0x00 => (true, KeyCode::Char(' ')),
+        // Ctrl+\, Ctrl+], Ctrl+^ and Ctrl+_
+        0x1c..=0x1f => (true, KeyCode::Char((byte + 0x40) as char)),
+        // Ctrl+A through Ctrl+Z (ESC is handled by the callers)
+        _ => (true, KeyCode::Char((byte + 0x60) as char)),
+    }
+}
+
 fn parse_escape_sequence(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)> {
     // Need at least 2 bytes for escape sequences
     if bytes.len() == 1 {
@@ -213,11 +1291,11 @@ fn parse_alt_char(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize
     let (ctrl, code) = if bytes[1] < 0x20 {
         // Control characters with Alt
         match bytes[1] {
-            0x0D => (false, KeyCode::Enter),
-            0x09 => (false, KeyCode::Tab),
             0x08 => (false, KeyCode::Backspace),
-            c => (true, KeyCode::Char((c + 0x60) as char)),
+            c => decode_control_char(c),
         }
+    } else if bytes[1] == 0x7f {
+        (false, KeyCode::Backspace)
     } else {
         (false, KeyCode::Char(c))
     };
@@ -232,12 +1310,22 @@ fn parse_csi_sequence(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, u
     }
 
     match bytes[2] {
-        b'<' => parse_sgr_mouse_sequence(bytes),
-        b'M' => parse_x10_mouse_sequence(bytes),
-        b'A'..=b'D' | b'H' | b'F' | b'Z' => parse_simple_csi_key(bytes),
-        b'1'..=b'6' => parse_complex_csi_key(bytes),
-        _ => Ok((None, 3)), // Unknown CSI sequence
+        b'<' => return parse_sgr_mouse_sequence(bytes),
+        b'M' => return parse_x10_mouse_sequence(bytes, false),
+        _ => {}
     }
+
+    let Some((params, final_byte, size)) = split_csi_sequence(bytes) else {
+        return Ok((None, 0)); // Need more bytes
+    };
+    let input = parse_csi_params(params).and_then(|params| match (final_byte, &params[..]) {
+        // urxvt mouse encoding (mode 1015): ESC [ button ; x ; y M
+        (b'M', &[button, x, y]) => {
+            create_mouse_input(button.saturating_sub(32), x, y, None).map(TerminalInput::Mouse)
+        }
+        _ => parse_csi_key(&params, final_byte),
+    });
+    Ok((input, size)) // Unknown CSI sequences are discarded
 }
 
 fn parse_ss3_sequence(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)> {
@@ -253,98 +1341,125 @@ fn parse_ss3_sequence(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, u
         b'D' => KeyCode::Left,
         b'H' => KeyCode::Home,
         b'F' => KeyCode::End,
+        b'P'..=b'S' => KeyCode::F(bytes[2] - b'P' + 1),
+        // Keypad keys in the application keypad mode
+        b'M' => KeyCode::KeypadEnter,
+        b'X' => KeyCode::Keypad('='),
+        b'j'..=b'y' => KeyCode::Keypad(char::from(
+            b"*+,-./0123456789"[usize::from(bytes[2] - b'j')],
+        )),
         _ => return Ok((None, 3)), // Unknown SS3 sequence
     };
 
     Ok((Some(create_key_input(false, false, code)), 3))
 }
 
-fn parse_simple_csi_key(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)> {
-    let code = match bytes[2] {
-        b'A' => KeyCode::Up,
-        b'B' => KeyCode::Down,
-        b'C' => KeyCode::Right,
-        b'D' => KeyCode::Left,
-        b'H' => KeyCode::Home,
-        b'F' => KeyCode::End,
-        b'Z' => KeyCode::BackTab,
-        _ => return Ok((None, 3)),
-    };
-
-    Ok((Some(create_key_input(false, false, code)), 3))
-}
-
-fn parse_complex_csi_key(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)> {
-    // Handle sequences like ESC [ 1 ; 5 A (modified arrow keys)
-    if bytes.len() >= 6 && bytes[2] == b'1' && bytes[3] == b';' && matches!(bytes[5], b'A'..=b'D') {
-        return parse_modified_arrow_key(bytes);
-    }
-
-    // Handle sequences like ESC [ 3 ~ (Delete) or ESC [ 3 ; 5 ~ (Ctrl+Delete)
-    if bytes.len() >= 4 && bytes[3] == b'~' {
-        return parse_special_key_simple(bytes);
+/// Splits a CSI sequence (`ESC [ <params> <final>`) at the beginning of `bytes`
+/// into its parameter bytes, final byte and total length.
+///
+/// Returns `None` if the sequence is incomplete.
+/// A malformed sequence is reported with a final byte of `0`, up to the offending byte.
+fn split_csi_sequence(bytes: &[u8]) -> Option<(&[u8], u8, usize)> {
+    for (i, &b) in bytes.iter().enumerate().skip(2) {
+        match b {
+            0x20..=0x3f => {}
+            0x40..=0x7e => return Some((&bytes[2..i], b, i + 1)),
+            _ => return Some((&[], 0, i)),
+        }
     }
+    None
+}
 
-    if bytes.len() >= 6 && bytes[3] == b';' && bytes[5] == b'~' {
-        return parse_special_key_with_modifier(bytes);
-    }
-
-    // Need more bytes or unknown sequence
-    if bytes.len() < 6 {
-        Ok((None, 0))
-    } else {
-        Ok((None, 3))
+/// Parses `;`-separated numeric CSI parameters, where omitted parameters default to 1.
+///
+/// Returns `None` if the parameters contain non-numeric bytes (e.g., private markers).
+fn parse_csi_params(params: &[u8]) -> Option<Vec<u16>> {
+    if params.is_empty() {
+        return Some(Vec::new());
     }
+    params
+        .split(|&b| b == b';')
+        .map(|param| {
+            // Sub-parameters (e.g., `1:2`) are ignored
+            let param = param.split(|&b| b == b':').next().unwrap_or_default();
+            if param.is_empty() {
+                return Some(1);
+            }
+            std::str::from_utf8(param).ok()?.parse().ok()
+        })
+        .collect()
 }
 
-fn parse_modified_arrow_key(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)> {
-    let modifier = bytes[4] - b'0';
-    let alt = modifier & 0x2 != 0;
-    let ctrl = modifier & 0x4 != 0;
+fn parse_csi_key(params: &[u16], final_byte: u8) -> Option<TerminalInput> {
+    // modifyOtherKeys reports (`CSI 27 ; <modifier> ; <code> ~`, or
+    // `CSI <code> ; <modifier> u` if the terminal's formatOtherKeys is enabled)
+    match (final_byte, params) {
+        (b'~', &[27, modifier, code]) | (b'u', &[code, modifier]) => {
+            return parse_other_key(code, modifier);
+        }
+        (b'u', &[code]) => return parse_other_key(code, 1),
+        _ => {}
+    }
 
-    let code = match bytes[5] {
-        b'A' => KeyCode::Up,
-        b'B' => KeyCode::Down,
-        b'C' => KeyCode::Right,
-        b'D' => KeyCode::Left,
-        _ => return Ok((None, 6)),
+    let (key, modifier) = match (final_byte, params) {
+        (b'~', &[key]) => (key, 1),
+        (b'~', &[key, modifier]) => (key, modifier),
+        (b'~', _) => return None,
+        (_, []) => (1, 1),
+        (_, &[1, modifier]) => (1, modifier),
+        _ => return None,
     };
 
-    Ok((Some(create_key_input(ctrl, alt, code)), 6))
-}
-
-fn parse_special_key_simple(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)> {
-    let code = match bytes[2] {
-        b'1' | b'7' => KeyCode::Home,
-        b'2' => KeyCode::Insert,
-        b'3' => KeyCode::Delete,
-        b'4' | b'8' => KeyCode::End,
-        b'5' => KeyCode::PageUp,
-        b'6' => KeyCode::PageDown,
-        _ => return Ok((None, 4)),
+    let code = match (final_byte, key) {
+        (b'A', _) => KeyCode::Up,
+        (b'B', _) => KeyCode::Down,
+        (b'C', _) => KeyCode::Right,
+        (b'D', _) => KeyCode::Left,
+        (b'H', _) => KeyCode::Home,
+        (b'F', _) => KeyCode::End,
+        (b'Z', _) => KeyCode::BackTab,
+        (b'P'..=b'S', _) => KeyCode::F(final_byte - b'P' + 1),
+        (b'~', 1 | 7) => KeyCode::Home,
+        (b'~', 2) => KeyCode::Insert,
+        (b'~', 3) => KeyCode::Delete,
+        (b'~', 4 | 8) => KeyCode::End,
+        (b'~', 5) => KeyCode::PageUp,
+        (b'~', 6) => KeyCode::PageDown,
+        (b'~', 11..=15) => KeyCode::F((key - 10) as u8),
+        (b'~', 17..=21) => KeyCode::F((key - 11) as u8),
+        (b'~', 23..=26) => KeyCode::F((key - 12) as u8),
+        (b'~', 28..=29) => KeyCode::F((key - 13) as u8),
+        (b'~', 31..=34) => KeyCode::F((key - 14) as u8),
+        _ => return None,
     };
 
-    Ok((Some(create_key_input(false, false, code)), 4))
+    let (ctrl, alt) = decode_key_modifier(modifier);
+    Some(create_key_input(ctrl, alt, code))
 }
 
-fn parse_special_key_with_modifier(
-    bytes: &[u8],
-) -> std::io::Result<(Option<TerminalInput>, usize)> {
-    let code = match bytes[2] {
-        b'1' | b'7' => KeyCode::Home,
-        b'2' => KeyCode::Insert,
-        b'3' => KeyCode::Delete,
-        b'4' | b'8' => KeyCode::End,
-        b'5' => KeyCode::PageUp,
-        b'6' => KeyCode::PageDown,
-        _ => return Ok((None, 6)),
+/// Decodes a key reported by xterm's modifyOtherKeys, given as a character code and a modifier.
+fn parse_other_key(code: u16, modifier: u16) -> Option<TerminalInput> {
+    let (ctrl, alt) = decode_key_modifier(modifier);
+    let shift = modifier.saturating_sub(1) & 0x1 != 0;
+    let code = match code {
+        0x0d => KeyCode::Enter,
+        0x09 if shift => KeyCode::BackTab,
+        0x09 => KeyCode::Tab,
+        0x1b => KeyCode::Escape,
+        0x08 | 0x7f => KeyCode::Backspace,
+        // Shift is only reflected in the case of letters, as [`KeyInput`] has no Shift flag
+        c => {
+            let c = char::from_u32(c.into())?;
+            KeyCode::Char(if shift { c.to_ascii_uppercase() } else { c })
+        }
     };
+    Some(create_key_input(ctrl, alt, code))
+}
 
-    let modifier = bytes[4] - b'0';
-    let alt = modifier & 0x2 != 0;
-    let ctrl = modifier & 0x4 != 0;
-
-    Ok((Some(create_key_input(ctrl, alt, code)), 6))
+/// Decodes an xterm key modifier parameter (1 + Shift(1) + Alt(2) + Ctrl(4)) into `(ctrl, alt)`.
+fn decode_key_modifier(modifier: u16) -> (bool, bool) {
+    let bits = modifier.saturating_sub(1);
+    (bits & 0x4 != 0, bits & 0x2 != 0)
 }
 
 fn parse_sgr_mouse_sequence(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)> {
@@ -380,24 +1495,44 @@ fn parse_sgr_mouse_sequence(bytes: &[u8]) -> std::io::Result<(Option<TerminalInp
         _ => return Ok((None, end + 1)), // Invalid parameters
     };
 
-    let mouse_input = create_sgr_mouse_input(button, x, y, bytes[end] == b'm')?;
+    let mouse_input = create_mouse_input(button, x, y, Some(bytes[end] == b'm'));
     match mouse_input {
         Some(input) => Ok((Some(TerminalInput::Mouse(input)), end + 1)),
         None => Ok((None, end + 1)),
     }
 }
 
-fn parse_x10_mouse_sequence(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)> {
-    if bytes.len() < 6 {
-        return Ok((None, 0));
+fn parse_x10_mouse_sequence(
+    bytes: &[u8],
+    utf8: bool,
+) -> std::io::Result<(Option<TerminalInput>, usize)> {
+    // Each value is offset by 32. In the UTF-8 encoding (mode 1005), values of 128 or more
+    // are sent as two-byte UTF-8 characters, which extends coordinates beyond 223.
+    // Bytes that do not start a two-byte character are taken as X10 values.
+    let mut values = [0u16; 3];
+    let mut offset = 3;
+    for value in &mut values {
+        let Some(&b) = bytes.get(offset) else {
+            return Ok((None, 0));
+        };
+        if utf8 && (0xc2..=0xdf).contains(&b) {
+            let Some(&next) = bytes.get(offset + 1) else {
+                return Ok((None, 0)); // Need more bytes
+            };
+            if next & 0xc0 != 0x80 {
+                return Ok((None, offset)); // Malformed report
+            }
+            *value = ((b as u16 & 0x1f) << 6) | (next as u16 & 0x3f);
+            offset += 2;
+        } else {
+            *value = b as u16;
+            offset += 1;
+        }
     }
 
-    let button_byte = bytes[3];
-    let x = bytes[4] as u16;
-    let y = bytes[5] as u16;
-
-    let mouse_input = create_x10_mouse_input(button_byte, x, y);
-    Ok((Some(TerminalInput::Mouse(mouse_input)), 6))
+    let [button, x, y] = values.map(|v| v.saturating_sub(32));
+    let mouse_input = create_mouse_input(button, x, y, None);
+    Ok((mouse_input.map(TerminalInput::Mouse), offset))
 }
 
 fn parse_utf8_char(bytes: &[u8]) -> std::io::Result<(Option<TerminalInput>, usize)> {
@@ -432,95 +1567,66 @@ fn create_key_input(ctrl: bool, alt: bool, code: KeyCode) -> TerminalInput {
     TerminalInput::Key(KeyInput { ctrl, alt, code })
 }
 
-fn create_sgr_mouse_input(
-    button: u16,
-    x: u16,
-    y: u16,
-    is_release: bool,
-) -> std::io::Result<Option<MouseInput>> {
-    let button_code = button & 0x03;
-    let ctrl = (button & 0x10) != 0;
-    let alt = (button & 0x08) != 0;
-    let shift = (button & 0x04) != 0;
-    let drag = (button & 0x20) != 0;
-
-    let event = if drag {
-        MouseEvent::Drag
-    } else if is_release {
-        match button_code {
-            0 => MouseEvent::LeftRelease,
-            1 => MouseEvent::MiddleRelease,
-            2 => MouseEvent::RightRelease,
-            _ => return Ok(None),
-        }
-    } else {
-        // Check for scroll events first
-        match button {
-            64 => MouseEvent::ScrollUp,
-            65 => MouseEvent::ScrollDown,
-            _ => match button_code {
-                0 => MouseEvent::LeftPress,
-                1 => MouseEvent::MiddlePress,
-                2 => MouseEvent::RightPress,
-                _ => return Ok(None),
-            },
-        }
-    };
+/// Creates a mouse input from a decoded report with 1-based coordinates.
+///
+/// `is_release` is `None` for the X10 and urxvt encodings (see [`decode_mouse_event()`]).
+fn create_mouse_input(button: u16, x: u16, y: u16, is_release: Option<bool>) -> Option<MouseInput> {
+    let event = decode_mouse_event(button, is_release)?;
 
-    Ok(Some(MouseInput {
+    Some(MouseInput {
         event,
         position: TerminalPosition::row_col(
             y.saturating_sub(1) as usize,
             x.saturating_sub(1) as usize,
         ),
-        ctrl,
-        alt,
-        shift,
-    }))
+        ctrl: (button & 0x10) != 0,
+        alt: (button & 0x08) != 0,
+        shift: (button & 0x04) != 0,
+        pixel_position: None,
+    })
 }
 
-fn create_x10_mouse_input(button_byte: u8, x: u16, y: u16) -> MouseInput {
-    let ctrl = (button_byte & 0x10) != 0;
-    let alt = (button_byte & 0x08) != 0;
-    let shift = (button_byte & 0x04) != 0;
-
-    let event = match button_byte {
-        96 => MouseEvent::ScrollUp,
-        97 => MouseEvent::ScrollDown,
-        _ => {
-            // Remove modifier bits to get the base button code
-            let base_button = button_byte & !0x1C; // Remove shift(4), alt(8), ctrl(16) bits
-
-            match base_button {
-                32 => MouseEvent::LeftPress,   // 0x20
-                33 => MouseEvent::MiddlePress, // 0x21
-                34 => MouseEvent::RightPress,  // 0x22
-                35 => MouseEvent::LeftRelease, // 0x23
-                64 => MouseEvent::Drag,        // 0x40
-                _ => {
-                    // Fallback: check bottom 2 bits for button type
-                    match button_byte & 0x03 {
-                        0 => MouseEvent::LeftPress,
-                        1 => MouseEvent::MiddlePress,
-                        2 => MouseEvent::RightPress,
-                        3 => MouseEvent::LeftRelease,
-                        _ => MouseEvent::LeftPress,
-                    }
-                }
-            }
-        }
+/// Decodes the button code of a mouse report, ignoring the modifier bits.
+///
+/// `is_release` is `None` for the X10 and urxvt encodings, where every release is reported
+/// as button 3 without telling which button was released.
+fn decode_mouse_event(button: u16, is_release: Option<bool>) -> Option<MouseEvent> {
+    let low_bits = (button & 0x03) as u8;
+    let motion = (button & 0x20) != 0;
+
+    let button = match (button & 0xc0, low_bits) {
+        (0x00, 0) => Some(MouseButton::Left),
+        (0x00, 1) => Some(MouseButton::Middle),
+        (0x00, 2) => Some(MouseButton::Right),
+        (0x00, _) => None,
+        (0x40, _) if motion || is_release == Some(true) => return None,
+        (0x40, 0) => return Some(MouseEvent::ScrollUp),
+        (0x40, 1) => return Some(MouseEvent::ScrollDown),
+        (0x40, 2) => return Some(MouseEvent::ScrollLeft),
+        (0x40, _) => return Some(MouseEvent::ScrollRight),
+        (0x80, n) => Some(MouseButton::Extra(8 + n)),
+        _ => return None,
     };
 
-    MouseInput {
-        event,
-        position: TerminalPosition::row_col(
-            y.saturating_sub(33) as usize,
-            x.saturating_sub(33) as usize,
-        ),
-        ctrl,
-        alt,
-        shift,
-    }
+    let event = match (button, motion, is_release) {
+        (Some(button), true, _) => MouseEvent::Drag(button),
+        (None, true, _) => MouseEvent::Move,
+        (None, false, None) => MouseEvent::LeftRelease,
+        (None, false, Some(_)) => return None,
+        (Some(button), false, Some(true)) => match button {
+            MouseButton::Left => MouseEvent::LeftRelease,
+            MouseButton::Middle => MouseEvent::MiddleRelease,
+            MouseButton::Right => MouseEvent::RightRelease,
+            MouseButton::Extra(n) => MouseEvent::ExtraRelease(n),
+        },
+        (Some(button), false, _) => match button {
+            MouseButton::Left => MouseEvent::LeftPress,
+            MouseButton::Middle => MouseEvent::MiddlePress,
+            MouseButton::Right => MouseEvent::RightPress,
+            MouseButton::Extra(n) => MouseEvent::ExtraPress(n),
+        },
+    };
+    Some(event)
 }
 
 #[cfg(test)]
@@ -613,6 +1719,71 @@ mod tests {
             }))
         );
         assert_eq!(result.1, 1);
+
+        // Ctrl+Space (NUL) and Ctrl+\ through Ctrl+_ (0x1C-0x1F), with and without Alt
+        let cases = [
+            (0x00, ' '),
+            (0x1c, '\\'),
+            (0x1d, ']'),
+            (0x1e, '^'),
+            (0x1f, '_'),
+        ];
+        for (byte, c) in cases {
+            assert_eq!(
+                parse_input(&[byte]).unwrap(),
+                (Some(create_key_input(true, false, KeyCode::Char(c))), 1)
+            );
+            assert_eq!(
+                parse_input(&[0x1b, byte]).unwrap(),
+                (Some(create_key_input(true, true, KeyCode::Char(c))), 2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_linefeed_as_enter() {
+        let mut parser = InputParser::new();
+        parser.feed(b"\n");
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(true, false, KeyCode::Char('j')))
+        );
+
+        parser.set_linefeed_as_enter(true);
+        parser.feed(b"\n\x1b\n\r");
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(false, false, KeyCode::Enter))
+        );
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(false, true, KeyCode::Enter))
+        );
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(false, false, KeyCode::Enter))
+        );
+        assert_eq!(parser.next_input(), None);
+    }
+
+    #[test]
+    fn test_parse_modify_other_keys() {
+        let cases: [(&[u8], bool, bool, KeyCode); 8] = [
+            (b"\x1b[27;5;49~", true, false, KeyCode::Char('1')),
+            (b"\x1b[27;6;65~", true, false, KeyCode::Char('A')),
+            (b"\x1b[27;6;97~", true, false, KeyCode::Char('A')),
+            (b"\x1b[27;5;13~", true, false, KeyCode::Enter),
+            (b"\x1b[27;7;127~", true, true, KeyCode::Backspace),
+            (b"\x1b[27;2;9~", false, false, KeyCode::BackTab),
+            (b"\x1b[27;3;233~", false, true, KeyCode::Char('é')),
+            (b"\x1b[49;5u", true, false, KeyCode::Char('1')),
+        ];
+        for (bytes, ctrl, alt, code) in cases {
+            assert_eq!(
+                parse_input(bytes).unwrap(),
+                (Some(create_key_input(ctrl, alt, code)), bytes.len())
+            );
+        }
     }
 
     #[test]
@@ -686,6 +1857,18 @@ mod tests {
             }))
         );
         assert_eq!(result.1, 2);
+
+        // Alt+Backspace
+        let result = parse_input(&[0x1b, 0x7f]).unwrap();
+        assert_eq!(
+            result.0,
+            Some(TerminalInput::Key(KeyInput {
+                ctrl: false,
+                alt: true,
+                code: KeyCode::Backspace,
+            }))
+        );
+        assert_eq!(result.1, 2);
     }
 
     #[test]
@@ -1010,8 +2193,8 @@ mod tests {
         assert_eq!(result.0, None);
         assert_eq!(result.1, 3);
 
-        // Unknown ESC O sequence
-        let result = parse_input(&[0x1b, b'O', b'X']).unwrap();
+        // Unknown ESC O sequence (`ESC O X` is the keypad `=`)
+        let result = parse_input(&[0x1b, b'O', b'Z']).unwrap();
         assert_eq!(result.0, None);
         assert_eq!(result.1, 3);
 
@@ -1085,6 +2268,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1099,6 +2283,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
     }
@@ -1116,6 +2301,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
         assert_eq!(result.1, input.len());
@@ -1131,6 +2317,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1145,6 +2332,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
     }
@@ -1162,6 +2350,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1176,6 +2365,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1190,6 +2380,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
     }
@@ -1207,6 +2398,7 @@ mod tests {
                 ctrl: true,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1221,6 +2413,7 @@ mod tests {
                 ctrl: false,
                 alt: true,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1235,6 +2428,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: true,
+                pixel_position: None,
             }))
         );
 
@@ -1249,6 +2443,7 @@ mod tests {
                 ctrl: true,
                 alt: true,
                 shift: true,
+                pixel_position: None,
             }))
         );
     }
@@ -1260,11 +2455,12 @@ mod tests {
         assert_eq!(
             result.0,
             Some(TerminalInput::Mouse(MouseInput {
-                event: MouseEvent::Drag,
+                event: MouseEvent::Drag(MouseButton::Left),
                 position: TerminalPosition::row_col(4, 9),
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1274,11 +2470,12 @@ mod tests {
         assert_eq!(
             result.0,
             Some(TerminalInput::Mouse(MouseInput {
-                event: MouseEvent::Drag,
+                event: MouseEvent::Drag(MouseButton::Left),
                 position: TerminalPosition::row_col(4, 9),
                 ctrl: true,
                 alt: true,
                 shift: true,
+                pixel_position: None,
             }))
         );
     }
@@ -1297,6 +2494,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
         assert_eq!(result.1, 6);
@@ -1313,6 +2511,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1328,6 +2527,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1343,6 +2543,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
     }
@@ -1360,6 +2561,7 @@ mod tests {
                 ctrl: true,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1374,6 +2576,7 @@ mod tests {
                 ctrl: false,
                 alt: true,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1388,6 +2591,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: true,
+                pixel_position: None,
             }))
         );
     }
@@ -1405,6 +2609,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1419,6 +2624,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
     }
@@ -1431,11 +2637,12 @@ mod tests {
         assert_eq!(
             result.0,
             Some(TerminalInput::Mouse(MouseInput {
-                event: MouseEvent::Drag,
+                event: MouseEvent::Drag(MouseButton::Left),
                 position: TerminalPosition::row_col(5, 10),
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
     }
@@ -1453,6 +2660,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1467,6 +2675,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
     }
@@ -1484,6 +2693,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1498,6 +2708,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
     }
@@ -1517,6 +2728,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
 
@@ -1533,6 +2745,7 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
             }))
         );
         assert_eq!(
@@ -1543,7 +2756,817 @@ mod tests {
                 ctrl: false,
                 alt: false,
                 shift: false,
+                pixel_position: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_input_reader_osc_response() {
+        use std::io::Cursor;
+
+        // Replies are kept out of the input stream
+        let mut reader =
+            InputReader::new(Cursor::new(b"a\x1b]52;c;Zm9v\x07b\x1b]11;rgb:0/0/0\x1b\\"));
+        reader.parser_mut().set_awaiting_response(true);
+        reader.fill_buf().unwrap();
+        reader.parser_mut().queue_inputs();
+        assert_eq!(
+            reader.parser_mut().take_response(|r| Some(r.clone())),
+            Some(TerminalResponse::Osc("52;c;Zm9v".to_owned()))
+        );
+        assert_eq!(
+            reader.parser_mut().take_response(|r| Some(r.clone())),
+            Some(TerminalResponse::Osc("11;rgb:0/0/0".to_owned()))
+        );
+        assert_eq!(
+            reader.read_input().unwrap(),
+            Some(create_key_input(false, false, KeyCode::Char('a')))
+        );
+        assert_eq!(
+            reader.read_input().unwrap(),
+            Some(create_key_input(false, false, KeyCode::Char('b')))
+        );
+
+        // Incomplete replies wait for more bytes
+        assert_eq!(parse_response(b"\x1b]52;c;Zm", false), Some((None, 0)));
+        assert_eq!(
+            parse_response(b"\x1b]52;c;Zm9v\x1b", false),
+            Some((None, 0))
+        );
+        assert_eq!(parse_response(b"\x1b]", true), Some((None, 0)));
+
+        // Alt+] is not a reply unless one is expected
+        assert_eq!(parse_response(b"\x1b]", false), None);
+        assert_eq!(parse_response(b"\x1b]a", true), None);
+        assert_eq!(
+            parse_input(b"\x1b]").unwrap(),
+            (Some(create_key_input(false, true, KeyCode::Char(']'))), 2)
+        );
+    }
+
+    #[test]
+    fn test_input_reader_long_sequence() {
+        use std::io::Cursor;
+
+        // A sequence longer than the initial buffer
+        let mut bytes = b"\x1b]52;c;".to_vec();
+        bytes.extend(std::iter::repeat_n(b'A', 200));
+        bytes.extend(b"\x07x");
+        let mut reader = InputReader::new(Cursor::new(bytes));
+        reader.parser_mut().set_awaiting_response(true);
+        let input = loop {
+            if let Some(input) = reader.read_input().unwrap() {
+                break input;
+            }
+        };
+        assert_eq!(input, create_key_input(false, false, KeyCode::Char('x')));
+        assert!(reader.parser_mut().take_response(|_| Some(())).is_some());
+    }
+
+    #[test]
+    fn test_parse_function_keys() {
+        let cases: [(&[u8], bool, bool, u8); 6] = [
+            (b"\x1bOP", false, false, 1),
+            (b"\x1bOS", false, false, 4),
+            (b"\x1b[15~", false, false, 5),
+            (b"\x1b[24~", false, false, 12),
+            (b"\x1b[1;5R", true, false, 3),
+            (b"\x1b[17;3~", false, true, 6),
+        ];
+        for (bytes, ctrl, alt, n) in cases {
+            assert_eq!(
+                parse_input(bytes).unwrap(),
+                (
+                    Some(create_key_input(ctrl, alt, KeyCode::F(n))),
+                    bytes.len()
+                )
+            );
+        }
+
+        // Shift is not reported as Alt
+        assert_eq!(
+            parse_input(b"\x1b[1;2R").unwrap(),
+            (Some(create_key_input(false, false, KeyCode::F(3))), 6)
+        );
+
+        // Unknown sequences are discarded as a whole
+        assert_eq!(parse_input(b"\x1b[?25;1x").unwrap(), (None, 8));
+        assert_eq!(parse_input(b"\x1b[15").unwrap(), (None, 0));
+    }
+
+    #[test]
+    fn test_parse_keypad_keys() {
+        let bytes = b"\x1bOM\x1bOk\x1bOm\x1bOp\x1bOy\x1bOX\x1bOA";
+        let mut parser = InputParser::new();
+        parser.feed(bytes);
+        assert_eq!(
+            parser.by_ref().collect::<Vec<_>>(),
+            [
+                create_key_input(false, false, KeyCode::Enter),
+                create_key_input(false, false, KeyCode::Char('+')),
+                create_key_input(false, false, KeyCode::Char('-')),
+                create_key_input(false, false, KeyCode::Char('0')),
+                create_key_input(false, false, KeyCode::Char('9')),
+                create_key_input(false, false, KeyCode::Char('=')),
+                create_key_input(false, false, KeyCode::Up),
+            ]
+        );
+
+        parser.set_distinct_keypad_keys(true);
+        parser.feed(bytes);
+        assert_eq!(
+            parser.collect::<Vec<_>>(),
+            [
+                create_key_input(false, false, KeyCode::KeypadEnter),
+                create_key_input(false, false, KeyCode::Keypad('+')),
+                create_key_input(false, false, KeyCode::Keypad('-')),
+                create_key_input(false, false, KeyCode::Keypad('0')),
+                create_key_input(false, false, KeyCode::Keypad('9')),
+                create_key_input(false, false, KeyCode::Keypad('=')),
+                create_key_input(false, false, KeyCode::Up),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rxvt_keys() {
+        let bytes = b"\x1b[a\x1bOd\x1b[2$\x1b[5^\x1b[11@\x1b[7~\x1b\x1b[A\x1b\x1bOc\x1b[1;5A";
+        let mut parser = InputParser::new();
+        parser.set_dialect(InputDialect::Rxvt);
+        parser.feed(bytes);
+        assert_eq!(
+            parser.by_ref().collect::<Vec<_>>(),
+            [
+                create_key_input(false, false, KeyCode::Up),
+                create_key_input(true, false, KeyCode::Left),
+                create_key_input(false, false, KeyCode::Insert),
+                create_key_input(true, false, KeyCode::PageUp),
+                create_key_input(true, false, KeyCode::F(1)),
+                create_key_input(false, false, KeyCode::Home),
+                create_key_input(false, true, KeyCode::Up),
+                create_key_input(true, true, KeyCode::Right),
+                create_key_input(true, false, KeyCode::Up),
+            ]
+        );
+
+        // Sequences split across chunks
+        for chunk in [&b"\x1b[2"[..], b"4$", b"\x1b", b"\x1b[", b"B"] {
+            parser.feed(chunk);
+            if let Some(input) = parser.next_input() {
+                assert!(parser.remainder().is_empty());
+                assert!(matches!(
+                    input,
+                    TerminalInput::Key(KeyInput {
+                        code: KeyCode::F(12) | KeyCode::Down,
+                        ..
+                    })
+                ));
+            }
+        }
+        assert!(parser.remainder().is_empty());
+
+        // Shifted (`$`), Ctrl (`^`) and Ctrl+Shift (`@`) function and editing keys
+        parser.feed(b"\x1b[23$\x1b[24$\x1b[15^\x1b[3^\x1b[6@\x1b[2@\x1b[9$\x1b[34");
+        assert_eq!(
+            parser.by_ref().collect::<Vec<_>>(),
+            [
+                create_key_input(false, false, KeyCode::F(11)),
+                create_key_input(false, false, KeyCode::F(12)),
+                create_key_input(true, false, KeyCode::F(5)),
+                create_key_input(true, false, KeyCode::Delete),
+                create_key_input(true, false, KeyCode::PageDown),
+                create_key_input(true, false, KeyCode::Insert),
+            ]
+        );
+        assert_eq!(parser.remainder(), b"\x1b[34");
+        parser.feed(b"@");
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(true, false, KeyCode::F(20)))
+        );
+
+        // `ESC ESC` followed by nothing else is Escape, not a pending Alt + key sequence
+        parser.feed(b"\x1b\x1b");
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(false, false, KeyCode::Escape))
+        );
+        assert_eq!(parser.remainder(), b"\x1b");
+        parser.feed(b"x");
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(false, true, KeyCode::Char('x')))
+        );
+
+        // A lone ESC before a non-key sequence is kept as Escape
+        parser.feed(b"\x1b\x1b[<0;1;1M");
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(false, false, KeyCode::Escape))
+        );
+        assert!(matches!(parser.next_input(), Some(TerminalInput::Mouse(_))));
+
+        // The xterm dialect leaves these sequences undecoded
+        let mut parser = InputParser::new();
+        parser.feed(b"\x1b[a\x1b[5^");
+        assert_eq!(parser.by_ref().count(), 0);
+    }
+
+    #[test]
+    fn test_parse_linux_console_keys() {
+        let mut parser = InputParser::new();
+        parser.set_dialect(InputDialect::Linux);
+        parser.feed(b"\x1b[[A\x1b[[E\x1b[[Zx\x1b[1~\x1b[4~\x1b[[");
+        assert_eq!(
+            parser.by_ref().collect::<Vec<_>>(),
+            [
+                create_key_input(false, false, KeyCode::F(1)),
+                create_key_input(false, false, KeyCode::F(5)),
+                create_key_input(false, false, KeyCode::Char('x')),
+                create_key_input(false, false, KeyCode::Home),
+                create_key_input(false, false, KeyCode::End),
+            ]
+        );
+        assert_eq!(parser.remainder(), b"\x1b[[");
+        parser.feed(b"C");
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(false, false, KeyCode::F(3)))
+        );
+
+        assert_eq!(InputDialect::from_term("linux"), InputDialect::Linux);
+        assert_eq!(InputDialect::from_term("rxvt"), InputDialect::Rxvt);
+        assert_eq!(
+            InputDialect::from_term("screen-256color"),
+            InputDialect::Xterm
+        );
+    }
+
+    #[test]
+    fn test_timed_input() {
+        use std::time::Duration;
+
+        let t0 = Instant::now();
+        let t = |ms| t0 + Duration::from_millis(ms);
+        let mut parser = InputParser::new();
+
+        // Inputs take the time of the chunk containing their last byte
+        parser.feed_at(b"ab\x1b[", t(0));
+        parser.feed_at(b"", t(5));
+        parser.feed_at(b"1;5", t(10));
+        parser.feed_at(b"Ac\x1b[999999999", t(20));
+        parser.feed_at(b"9Xd", t(30));
+        let timed = |parser: &mut InputParser| {
+            std::iter::from_fn(|| parser.next_timed_input())
+                .map(|(input, time)| (input.to_string(), time))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            timed(&mut parser),
+            [
+                ("a".to_owned(), t(0)),
+                ("b".to_owned(), t(0)),
+                ("Ctrl+Up".to_owned(), t(20)),
+                ("c".to_owned(), t(20)),
+                ("d".to_owned(), t(30)),
+            ]
+        );
+
+        // Queued inputs keep their times
+        parser.feed_at(b"ab\x1b[", t(40));
+        parser.feed_at(b"Ae", t(50));
+        parser.queue_inputs();
+        parser.feed_at(b"f", t(60));
+        assert_eq!(
+            timed(&mut parser),
+            [
+                ("a".to_owned(), t(40)),
+                ("b".to_owned(), t(40)),
+                ("Up".to_owned(), t(50)),
+                ("e".to_owned(), t(50)),
+                ("f".to_owned(), t(60)),
+            ]
+        );
+        assert!(parser.chunk_times.len() <= 2);
+    }
+
+    #[test]
+    fn test_input_reader_empty_read() {
+        use std::io::Cursor;
+
+        let mut reader = InputReader::new(Cursor::new(b""));
+        assert_eq!(
+            reader.fill_buf().map_err(|e| e.kind()),
+            Err(std::io::ErrorKind::UnexpectedEof)
+        );
+
+        // A timed out read (VMIN=0) is not EOF, unless the reader was reported readable
+        reader.set_empty_read_allowed(true);
+        assert!(reader.fill_buf().is_ok());
+        assert_eq!(reader.read_input().ok(), Some(None));
+        assert_eq!(
+            reader.fill_ready_buf().map_err(|e| e.kind()),
+            Err(std::io::ErrorKind::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn test_input_reader_cursor_position_report() {
+        use std::io::Cursor;
+
+        // The reply is kept out of the input stream while awaited
+        let mut reader = InputReader::new(Cursor::new(b"a\x1b[?12;40Rb"));
+        reader.parser_mut().set_awaiting_response(true);
+        reader.fill_buf().unwrap();
+        reader.parser_mut().queue_inputs();
+        assert_eq!(
+            reader.parser_mut().take_response(|r| Some(r.clone())),
+            Some(TerminalResponse::CursorPosition(TerminalPosition::row_col(
+                11, 39
+            )))
+        );
+        assert_eq!(
+            reader.read_input().unwrap(),
+            Some(create_key_input(false, false, KeyCode::Char('a')))
+        );
+        assert_eq!(
+            reader.read_input().unwrap(),
+            Some(create_key_input(false, false, KeyCode::Char('b')))
+        );
+
+        // The plain form is accepted unless it may be a modified F3 key
+        assert_eq!(parse_response(b"\x1b[1;2R", false), None);
+        assert_eq!(parse_response(b"\x1b[1;2R", true), None);
+        assert_eq!(parse_response(b"\x1b[1;16R", true), None);
+        for (bytes, row, col) in [
+            (&b"\x1b[12;40R"[..], 11, 39),
+            (b"\x1b[1;1R", 0, 0),
+            (b"\x1b[1;17R", 0, 16),
+        ] {
+            assert_eq!(
+                parse_response(bytes, true),
+                Some((
+                    Some(TerminalResponse::CursorPosition(TerminalPosition::row_col(
+                        row, col
+                    ))),
+                    bytes.len()
+                ))
+            );
+        }
+        assert_eq!(
+            parse_response(b"\x1b[?1;2;1R", true),
+            Some((
+                Some(TerminalResponse::CursorPosition(TerminalPosition::row_col(
+                    0, 1
+                ))),
+                9
+            ))
+        );
+        assert_eq!(parse_response(b"\x1b[?1;2", true), Some((None, 0)));
+        assert_eq!(parse_response(b"\x1b[A", true), None);
+
+        // Shift+F3 followed by the reply while awaiting
+        let mut parser = InputParser::new();
+        parser.set_awaiting_response(true);
+        parser.feed(b"\x1b[1;2R\x1b[?3;7R");
+        parser.queue_inputs();
+        assert_eq!(
+            parser.take_response(|r| Some(r.clone())),
+            Some(TerminalResponse::CursorPosition(TerminalPosition::row_col(
+                2, 6
+            )))
+        );
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(false, false, KeyCode::F(3)))
+        );
+        assert_eq!(parser.next_input(), None);
+    }
+
+    #[test]
+    fn test_parse_mouse_motion_with_button_identity() {
+        let cases: [(&[u8], MouseEvent); 6] = [
+            (b"\x1b[<33;10;5M", MouseEvent::Drag(MouseButton::Middle)),
+            (b"\x1b[<34;10;5M", MouseEvent::Drag(MouseButton::Right)),
+            (b"\x1b[<35;10;5M", MouseEvent::Move),
+            (b"\x1b[MA\x2a\x25", MouseEvent::Drag(MouseButton::Middle)),
+            (b"\x1b[MB\x2a\x25", MouseEvent::Drag(MouseButton::Right)),
+            (b"\x1b[MC\x2a\x25", MouseEvent::Move),
+        ];
+        for (bytes, event) in cases {
+            assert_eq!(
+                parse_input(bytes).unwrap(),
+                (
+                    Some(TerminalInput::Mouse(MouseInput {
+                        event,
+                        position: TerminalPosition::row_col(4, 9),
+                        ctrl: false,
+                        alt: false,
+                        shift: false,
+                        pixel_position: None,
+                    })),
+                    bytes.len()
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_mouse_horizontal_scroll_and_extra_buttons() {
+        let cases: [(&[u8], MouseEvent, bool); 10] = [
+            (b"\x1b[<66;10;5M", MouseEvent::ScrollLeft, false),
+            (b"\x1b[<67;10;5M", MouseEvent::ScrollRight, false),
+            (b"\x1b[<128;10;5M", MouseEvent::ExtraPress(8), false),
+            (b"\x1b[<129;10;5m", MouseEvent::ExtraRelease(9), false),
+            (
+                b"\x1b[<160;10;5M",
+                MouseEvent::Drag(MouseButton::Extra(8)),
+                false,
+            ),
+            // Modifiers are decoded for every button class
+            (b"\x1b[<80;10;5M", MouseEvent::ScrollUp, true),
+            (b"\x1b[<144;10;5M", MouseEvent::ExtraPress(8), true),
+            (b"\x1b[M\x62\x2a\x25", MouseEvent::ScrollLeft, false),
+            (b"\x1b[M\x70\x2a\x25", MouseEvent::ScrollUp, true),
+            (b"\x1b[M\xa0\x2a\x25", MouseEvent::ExtraPress(8), false),
+        ];
+        for (bytes, event, ctrl) in cases {
+            assert_eq!(
+                parse_input(bytes).unwrap(),
+                (
+                    Some(TerminalInput::Mouse(MouseInput {
+                        event,
+                        position: TerminalPosition::row_col(4, 9),
+                        ctrl,
+                        alt: false,
+                        shift: false,
+                        pixel_position: None,
+                    })),
+                    bytes.len()
+                ),
+                "{bytes:?}"
+            );
+        }
+
+        // Wheel releases are not reported
+        assert_eq!(parse_input(b"\x1b[<64;10;5m").unwrap(), (None, 11));
+    }
+
+    #[test]
+    fn test_parse_mouse_extended_encodings() {
+        let mouse = |event, row, col, ctrl| {
+            Some(TerminalInput::Mouse(MouseInput {
+                event,
+                position: TerminalPosition::row_col(row, col),
+                ctrl,
+                alt: false,
+                shift: false,
+                pixel_position: None,
+            }))
+        };
+
+        // urxvt (mode 1015)
+        assert_eq!(
+            parse_input(b"\x1b[32;300;5M").unwrap(),
+            (mouse(MouseEvent::LeftPress, 4, 299, false), 11)
+        );
+        assert_eq!(
+            parse_input(b"\x1b[51;10;5M").unwrap(),
+            (mouse(MouseEvent::LeftRelease, 4, 9, true), 10)
+        );
+        assert_eq!(
+            parse_input(b"\x1b[96;10;5M").unwrap(),
+            (mouse(MouseEvent::ScrollUp, 4, 9, false), 10)
+        );
+        assert_eq!(parse_input(b"\x1b[32;300;5").unwrap(), (None, 0));
+
+        // UTF-8 (mode 1005): column 300 is sent as U+014C (300 + 32)
+        assert_eq!(
+            parse_x10_mouse_sequence(b"\x1b[M \xc5\x8c\x25", true).unwrap(),
+            (mouse(MouseEvent::LeftPress, 4, 299, false), 7)
+        );
+        assert_eq!(
+            parse_x10_mouse_sequence(b"\x1b[M \xc5\x8c", true).unwrap(),
+            (None, 0)
+        );
+        assert_eq!(
+            parse_x10_mouse_sequence(b"\x1b[M \xc5", true).unwrap(),
+            (None, 0)
+        );
+
+        // Plain X10 values above 127 are taken as they are, even if they look like UTF-8
+        assert_eq!(
+            parse_input(b"\x1b[M \xc5\x25").unwrap(),
+            (mouse(MouseEvent::LeftPress, 4, 164, false), 6)
+        );
+        assert_eq!(
+            parse_input(b"\x1b[M \xc5\x8c").unwrap(),
+            (mouse(MouseEvent::LeftPress, 107, 164, false), 6)
+        );
+
+        // A UTF-8 report split between the bytes of a character
+        let mut parser = InputParser::new();
+        parser.set_utf8_mouse(true);
+        parser.feed(b"\x1b[M \xc5");
+        assert_eq!(parser.next_input(), None);
+        parser.feed(b"\x8c\x25a");
+        assert_eq!(
+            parser.next_input(),
+            mouse(MouseEvent::LeftPress, 4, 299, false)
+        );
+        assert_eq!(
+            parser.next_input(),
+            Some(create_key_input(false, false, KeyCode::Char('a')))
+        );
+        assert_eq!(parser.next_input(), None);
+
+        // Without UTF-8 decoding (the default), raw high bytes are plain X10 coordinates,
+        // including those in the range of UTF-8 lead bytes (0xC2-0xDF)
+        let mut parser = InputParser::new();
+        parser.feed(b"\x1b[M \xd0\xc3\x1b[M#\xdf");
+        assert_eq!(
+            parser.next_input(),
+            mouse(MouseEvent::LeftPress, 162, 175, false)
+        );
+        assert_eq!(parser.next_input(), None);
+        parser.feed(b"\xc2");
+        assert_eq!(
+            parser.next_input(),
+            mouse(MouseEvent::LeftRelease, 161, 190, false)
+        );
+        assert_eq!(parser.remainder(), b"");
+    }
+
+    #[test]
+    fn test_input_reader_pixel_mouse() {
+        use std::io::Cursor;
+
+        let mut reader = InputReader::new(Cursor::new(b"\x1b[<0;81;35M\x1b[6;16;8t"));
+        reader.parser_mut().set_pixel_mouse(true);
+        reader
+            .parser_mut()
+            .set_cell_pixel_size(PixelSize::width_height(8, 16));
+        reader.parser_mut().set_awaiting_response(true);
+        assert_eq!(
+            reader.read_input().unwrap(),
+            Some(TerminalInput::Mouse(MouseInput {
+                event: MouseEvent::LeftPress,
+                position: TerminalPosition::row_col(2, 10),
+                ctrl: false,
+                alt: false,
+                shift: false,
+                pixel_position: Some(PixelPosition::xy(80, 34)),
+            }))
+        );
+        reader.parser_mut().queue_inputs();
+        assert_eq!(
+            reader.parser_mut().take_response(|r| Some(r.clone())),
+            Some(TerminalResponse::CellPixelSize(PixelSize::width_height(
+                8, 16
+            )))
+        );
+    }
+
+    /// Reader that returns at most `chunk_size` bytes per read.
+    struct ChunkedReader {
+        bytes: Vec<u8>,
+        offset: usize,
+        chunk_size: usize,
+    }
+
+    impl Read for ChunkedReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf
+                .len()
+                .min(self.chunk_size)
+                .min(self.bytes.len() - self.offset);
+            buf[..n].copy_from_slice(&self.bytes[self.offset..][..n]);
+            self.offset += n;
+            Ok(n)
+        }
+    }
+
+    fn read_all_inputs(reader: &mut InputReader<ChunkedReader>) -> Vec<TerminalInput> {
+        let mut inputs = Vec::new();
+        loop {
+            if let Some(input) = reader.parser_mut().next_input() {
+                inputs.push(input);
+            } else if reader.inner().offset < reader.inner().bytes.len() {
+                reader.fill_buf().unwrap();
+            } else {
+                return inputs;
+            }
+        }
+    }
+
+    #[test]
+    fn test_input_reader_sequences_split_across_reads() {
+        let bytes = b"\x1b[<0;300;120M\x1b[1;5A\x1b[15~\x1b]52;c;Zm9v\x07\xe2\x82\xacx".to_vec();
+        for chunk_size in [1, 2, 3, 7] {
+            let mut reader = InputReader::new(ChunkedReader {
+                bytes: bytes.clone(),
+                offset: 0,
+                chunk_size,
+            });
+            reader.parser_mut().set_awaiting_response(true);
+            assert_eq!(
+                read_all_inputs(&mut reader),
+                [
+                    TerminalInput::Mouse(MouseInput {
+                        event: MouseEvent::LeftPress,
+                        position: TerminalPosition::row_col(119, 299),
+                        ctrl: false,
+                        alt: false,
+                        shift: false,
+                        pixel_position: None,
+                    }),
+                    create_key_input(true, false, KeyCode::Up),
+                    create_key_input(false, false, KeyCode::F(5)),
+                    create_key_input(false, false, KeyCode::Char('€')),
+                    create_key_input(false, false, KeyCode::Char('x')),
+                ],
+                "chunk_size={chunk_size}"
+            );
+            assert_eq!(
+                reader.parser_mut().take_response(|r| Some(r.clone())),
+                Some(TerminalResponse::Osc("52;c;Zm9v".to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_input_reader_overlong_sequences() {
+        let mut bytes = b"a\x1b]52;c;".to_vec();
+        bytes.extend(std::iter::repeat_n(b'A', 500));
+        bytes.extend(b"\x1b\\b\x1b[");
+        bytes.extend(std::iter::repeat_n(b'1', 500));
+        bytes.extend(b"Ac\x1b]2;");
+        bytes.extend(std::iter::repeat_n(b'x', 500));
+        bytes.extend(b"\x1b[Bd");
+
+        for chunk_size in [1, 10, 1000] {
+            let mut reader = InputReader::new(ChunkedReader {
+                bytes: bytes.clone(),
+                offset: 0,
+                chunk_size,
+            });
+            reader.parser_mut().set_max_sequence_len(128);
+            reader.parser_mut().set_awaiting_response(true);
+            assert_eq!(
+                read_all_inputs(&mut reader),
+                [
+                    create_key_input(false, false, KeyCode::Char('a')),
+                    create_key_input(false, false, KeyCode::Char('b')),
+                    create_key_input(false, false, KeyCode::Char('c')),
+                    // An unterminated string is cut off by the next sequence
+                    create_key_input(false, false, KeyCode::Down),
+                    create_key_input(false, false, KeyCode::Char('d')),
+                ],
+                "chunk_size={chunk_size}"
+            );
+            assert_eq!(reader.parser_mut().take_response(|r| Some(r.clone())), None);
+            assert!(reader.parser.remainder().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_input_parser_feed() {
+        let mut parser = InputParser::new();
+        let mut inputs = Vec::new();
+        for &b in b"\x1b[1;5Ax\xe2\x82\xac" {
+            parser.feed(&[b]);
+            inputs.extend(parser.by_ref());
+        }
+        assert_eq!(
+            inputs,
+            [
+                create_key_input(true, false, KeyCode::Up),
+                create_key_input(false, false, KeyCode::Char('x')),
+                create_key_input(false, false, KeyCode::Char('€')),
+            ]
+        );
+        assert!(parser.remainder().is_empty());
+
+        parser.feed(b"\x1b[<0;1");
+        assert_eq!(parser.next_input(), None);
+        assert_eq!(parser.remainder(), b"\x1b[<0;1");
+    }
+
+    #[test]
+    fn test_input_display_and_from_str() {
+        let key = |ctrl, alt, code| KeyInput { ctrl, alt, code };
+        let examples = [
+            (key(false, false, KeyCode::Char('a')), "a"),
+            (key(true, false, KeyCode::Char('a')), "Ctrl+a"),
+            (key(true, true, KeyCode::PageUp), "Ctrl+Alt+PageUp"),
+            (key(false, true, KeyCode::Char('+')), "Alt++"),
+            (key(false, false, KeyCode::Char(' ')), "Space"),
+            (key(true, false, KeyCode::Char('\x7f')), "Ctrl+U+007F"),
+            (key(false, false, KeyCode::F(12)), "F12"),
+            (key(false, false, KeyCode::Char('F')), "F"),
+        ];
+        for (input, text) in examples {
+            assert_eq!(input.to_string(), text);
+            assert_eq!(text.parse(), Ok(input));
+        }
+
+        // Aliases
+        assert_eq!("C-a".parse(), Ok(key(true, false, KeyCode::Char('a'))));
+        assert_eq!("c-m-esc".parse(), Ok(key(true, true, KeyCode::Escape)));
+        assert_eq!("ALT+pgdn".parse(), Ok(key(false, true, KeyCode::PageDown)));
+        assert_eq!(
+            "C-".parse(),
+            Err::<KeyInput, _>(r#"invalid key input: "C-""#.to_owned())
+        );
+        assert_eq!("Shift+Tab".parse(), Ok(key(false, false, KeyCode::BackTab)));
+        assert_eq!("M-S-Tab".parse(), Ok(key(false, true, KeyCode::BackTab)));
+        assert_eq!("cr".parse(), Ok(key(false, false, KeyCode::Enter)));
+        assert!("Shift+a".parse::<KeyInput>().is_err());
+        assert!("F0".parse::<KeyInput>().is_err());
+        assert!("Foo".parse::<KeyInput>().is_err());
+        assert!("".parse::<KeyInput>().is_err());
+
+        let mouse = MouseInput {
+            event: MouseEvent::Drag(MouseButton::Extra(9)),
+            position: TerminalPosition::row_col(4, 12),
+            ctrl: true,
+            alt: false,
+            shift: true,
+            pixel_position: Some(PixelPosition::xy(100, 67)),
+        };
+        assert_eq!(mouse.to_string(), "Ctrl+Shift+Button9Drag@12,4/100,67");
+        assert_eq!("ctrl+s-button9drag@12,4/100,67".parse(), Ok(mouse));
+        assert_eq!(
+            "LeftPress@12,4".parse(),
+            Ok(TerminalInput::Mouse(MouseInput {
+                event: MouseEvent::LeftPress,
+                position: TerminalPosition::row_col(4, 12),
+                ctrl: false,
+                alt: false,
+                shift: false,
+                pixel_position: None,
             }))
         );
+        for s in [
+            "LeftPress",
+            "LeftPress@12",
+            "LeftPress@12,4/",
+            "Foo@1,2",
+            "Press@1,2",
+        ] {
+            assert!(s.parse::<TerminalInput>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_input_text_round_trip() {
+        let mut samples: Vec<Vec<u8>> = Vec::new();
+        for b in 0..=0x7f {
+            samples.push(vec![b]);
+            samples.push(vec![0x1b, b]);
+        }
+        for c in ["é", "€", "\u{85}", "\u{3000}", "\u{1f600}"] {
+            samples.push(c.as_bytes().to_vec());
+        }
+        for m in ["", "1;2", "1;3", "1;5", "1;7", "1;8"] {
+            for f in "ABCDHFZPQRS".chars() {
+                samples.push(format!("\x1b[{m}{f}").into_bytes());
+            }
+        }
+        for n in (1..=8).chain(11..=34) {
+            samples.push(format!("\x1b[{n}~").into_bytes());
+            samples.push(format!("\x1b[{n};6~").into_bytes());
+        }
+        for c in [9, 13, 27, 32, 49, 65, 97, 127, 233] {
+            samples.push(format!("\x1b[27;6;{c}~").into_bytes());
+        }
+        for f in "ABCDHFPQRSMXjklmnopqrstuvwxy".chars() {
+            samples.push(format!("\x1bO{f}").into_bytes());
+        }
+        for b in 0..=0xff {
+            samples.push(format!("\x1b[<{b};12;4M").into_bytes());
+            samples.push(format!("\x1b[<{b};12;4m").into_bytes());
+        }
+        for b in 32..=0xff {
+            samples.push(vec![0x1b, b'[', b'M', b, 44, 36]);
+        }
+
+        let mut count = 0;
+        for modes_enabled in [false, true] {
+            for sample in &samples {
+                let mut parser = InputParser::new();
+                parser.set_pixel_mouse(modes_enabled);
+                parser.set_distinct_keypad_keys(modes_enabled);
+                parser.set_cell_pixel_size(PixelSize::width_height(8, 16));
+                parser.feed(sample);
+                for input in parser {
+                    let text = input.to_string();
+                    assert_eq!(text.parse(), Ok(input), "{text}");
+                    count += 1;
+                }
+            }
+        }
+        assert!(count > 1000);
     }
 }
@@ -0,0 +1,625 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Error, ErrorKind},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{KeyCode, KeyInput, TerminalInput, input::strip_modifiers};
+
+/// [`Keymap`] mode whose bindings apply in every mode.
+pub const GLOBAL_MODE: &str = "global";
+
+/// Mode of a newly created [`Keymap`].
+pub const DEFAULT_MODE: &str = "normal";
+
+/// Result of feeding a key to a [`Keymap`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KeymapEvent<A> {
+    /// The keys pressed so far completed a binding.
+    Action(A),
+
+    /// The keys pressed so far are a prefix of one or more bindings,
+    /// and more keys are needed to complete one of them.
+    Pending,
+
+    /// The keys pressed so far (including the last one) do not match any binding.
+    ///
+    /// Applications may handle them by themselves (e.g., insert characters in an insert mode).
+    Unbound(Vec<KeyInput>),
+}
+
+/// Resolver of key sequences (chords) to user-defined actions.
+///
+/// Bindings are defined per mode (e.g., `"normal"` and `"insert"` as in vim).
+/// Bindings in [`GLOBAL_MODE`] apply in every mode, unless the current mode
+/// has its own binding that starts with the same keys.
+///
+/// A binding is a whitespace-separated sequence of keys, such as `"C-x C-s"`, `"M-<Up>"`,
+/// `"<F5>"` or `"g g"`. Each key is written as:
+///
+/// - A single character (e.g., `a`, `G`, `/`)
+/// - A key name, optionally in angle brackets (e.g., `<Enter>`, `<Esc>`, `<PageUp>`, `<F5>`),
+///   as in the textual form of [`KeyCode`]; `<lt>` and `<gt>` stand for `<` and `>`
+/// - Either of the above prefixed by modifiers as in the textual form of [`KeyInput`]
+///   (e.g., `C-x`, `M-<Up>` or `Ctrl+Alt+Delete`); the modifiers may also be written
+///   inside the angle brackets (e.g., `<C-x>`)
+///
+/// As terminals send the same bytes for them, `C-i`, `C-m`, `C-[` and `C-@` are the same as
+/// `<Tab>`, `<Enter>`, `<Esc>` and `C-<Space>` (both in bindings and in pressed keys).
+/// The case of other characters is kept: `C-X` (Ctrl+Shift+X) differs from `C-x`, but
+/// terminals can only report it when `modifyOtherKeys` is enabled
+/// (see [`Terminal::set_modify_other_keys()`](crate::Terminal::set_modify_other_keys));
+/// otherwise, Ctrl+Shift+X is received as `C-x`.
+///
+/// A binding cannot be a prefix of another binding in the same mode, so a complete
+/// sequence is resolved as soon as its last key is pressed.
+/// A pending sequence is abandoned if the next key is not pressed within
+/// [`Keymap::chord_timeout()`], which is detected by [`Keymap::handle_timeout()`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Instant;
+/// use tuinix::{GLOBAL_MODE, Keymap, KeymapEvent, Terminal, TerminalEvent};
+///
+/// let mut keymap = Keymap::new();
+/// keymap.bind(GLOBAL_MODE, "C-x C-c", "quit")?;
+/// keymap.bind("normal", "i", "insert")?;
+/// keymap.bind("insert", "<Esc>", "normal")?;
+///
+/// let mut terminal = Terminal::new()?;
+/// loop {
+///     let timeout = keymap
+///         .next_deadline()
+///         .map(|deadline| deadline.saturating_duration_since(Instant::now()));
+///     let event = match terminal.poll_event(&[], &[], timeout)? {
+///         Some(TerminalEvent::Input(input)) => keymap.handle_input(input, Instant::now()),
+///         None => keymap.handle_timeout(Instant::now()),
+///         _ => None,
+///     };
+///     match event {
+///         Some(KeymapEvent::Action("quit")) => break,
+///         Some(KeymapEvent::Action(mode)) => keymap.set_mode(mode),
+///         _ => {}
+///     }
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keymap<A> {
+    modes: HashMap<String, BTreeMap<Vec<KeyInput>, A>>,
+    mode: String,
+    pending: Vec<KeyInput>,
+    last_key_time: Option<Instant>,
+    chord_timeout: Duration,
+}
+
+impl<A> Keymap<A> {
+    /// Makes a new keymap without any bindings, in [`DEFAULT_MODE`].
+    pub fn new() -> Self {
+        Self {
+            modes: HashMap::new(),
+            mode: DEFAULT_MODE.to_owned(),
+            pending: Vec::new(),
+            last_key_time: None,
+            chord_timeout: Duration::from_secs(1),
+        }
+    }
+
+    /// Binds the key sequence `keys` (e.g., `"C-x C-s"`) to `action` in `mode`.
+    ///
+    /// If `keys` is already bound in `mode`, its action is replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidInput`] error if `keys` is malformed or
+    /// is a prefix of another binding in `mode` (or vice versa).
+    pub fn bind(&mut self, mode: &str, keys: &str, action: A) -> std::io::Result<()> {
+        let keys = parse_key_sequence(keys)?;
+        self.bind_keys(mode, keys, action)
+    }
+
+    /// Binds the key sequence `keys` to `action` in `mode`.
+    ///
+    /// See [`Keymap::bind()`] for details.
+    pub fn bind_keys(&mut self, mode: &str, keys: Vec<KeyInput>, action: A) -> std::io::Result<()> {
+        if keys.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "empty key sequence"));
+        }
+        let keys = keys.into_iter().map(normalize_key).collect::<Vec<_>>();
+
+        let bindings = self.modes.entry(mode.to_owned()).or_default();
+        let conflicts = match lookup(bindings, &keys) {
+            Lookup::Exact(_) => false,
+            Lookup::Prefix => true,
+            Lookup::None => (1..keys.len()).any(|n| bindings.contains_key(&keys[..n])),
+        };
+        if conflicts {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "key sequence conflicts with a longer or shorter binding",
+            ));
+        }
+
+        bindings.insert(keys, action);
+        Ok(())
+    }
+
+    /// Loads bindings from a configuration text.
+    ///
+    /// Each line has the form `KEYS = ACTION`, where `ACTION` is parsed with [`FromStr`].
+    /// A `[MODE]` line makes the following bindings belong to `MODE`; bindings before the
+    /// first such line belong to [`GLOBAL_MODE`].
+    /// Empty lines and lines starting with `#` are ignored.
+    ///
+    /// ```text
+    /// C-x C-c = quit
+    ///
+    /// [normal]
+    /// g g = goto-top
+    /// <F5> = refresh
+    ///
+    /// [insert]
+    /// <Esc> = normal-mode
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidInput`] error, whose message contains the line number,
+    /// if a line is malformed or cannot be bound. Bindings on the preceding lines are kept.
+    pub fn load(&mut self, config: &str) -> std::io::Result<()>
+    where
+        A: FromStr,
+    {
+        let mut mode = GLOBAL_MODE.to_owned();
+        for (i, line) in config.lines().enumerate() {
+            let error = |reason: &dyn std::fmt::Display| {
+                Error::new(ErrorKind::InvalidInput, format!("line {}: {reason}", i + 1))
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                mode = name.trim().to_owned();
+                continue;
+            }
+
+            // Split at the last `=` so that `C-= = zoom-in` works
+            let Some((keys, action)) = line.rsplit_once('=') else {
+                return Err(error(&"expected `KEYS = ACTION`"));
+            };
+            let action = action.trim();
+            let action = action
+                .parse()
+                .map_err(|_| error(&format_args!("invalid action: {action:?}")))?;
+            self.bind(&mode, keys, action).map_err(|e| error(&e))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the current mode.
+    pub fn mode(&self) -> &str {
+        &self.mode
+    }
+
+    /// Switches to `mode` and abandons the pending key sequence, if any.
+    pub fn set_mode(&mut self, mode: &str) {
+        self.mode = mode.to_owned();
+        self.cancel_pending();
+    }
+
+    /// Returns the maximum time between keys of a sequence (1 second by default).
+    pub fn chord_timeout(&self) -> Duration {
+        self.chord_timeout
+    }
+
+    /// Sets the maximum time between keys of a sequence.
+    pub fn set_chord_timeout(&mut self, timeout: Duration) {
+        self.chord_timeout = timeout;
+    }
+
+    /// Returns the keys of the pending sequence (e.g., to show `C-x-` in a status line).
+    pub fn pending_keys(&self) -> &[KeyInput] {
+        &self.pending
+    }
+
+    /// Abandons the pending key sequence, if any.
+    pub fn cancel_pending(&mut self) {
+        self.pending.clear();
+        self.last_key_time = None;
+    }
+
+    /// Returns the time at which [`Keymap::handle_timeout()`] should be called
+    /// to abandon the pending key sequence, if any.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.last_key_time
+            .map(|timestamp| timestamp + self.chord_timeout)
+    }
+
+    /// Abandons the pending key sequence if [`Keymap::chord_timeout()`] has elapsed at `now`,
+    /// returning its keys as [`KeymapEvent::Unbound`].
+    pub fn handle_timeout(&mut self, now: Instant) -> Option<KeymapEvent<A>> {
+        if self.next_deadline().is_none_or(|deadline| now < deadline) {
+            return None;
+        }
+        self.last_key_time = None;
+        Some(KeymapEvent::Unbound(std::mem::take(&mut self.pending)))
+    }
+
+    fn lookup(&self, keys: &[KeyInput]) -> Lookup<'_, A> {
+        let in_mode = self
+            .modes
+            .get(&self.mode)
+            .map_or(Lookup::None, |bindings| lookup(bindings, keys));
+        match in_mode {
+            Lookup::None => self
+                .modes
+                .get(GLOBAL_MODE)
+                .map_or(Lookup::None, |bindings| lookup(bindings, keys)),
+            found => found,
+        }
+    }
+}
+
+impl<A: Clone> Keymap<A> {
+    /// Processes an input received at `timestamp` and returns the result, if any.
+    ///
+    /// Non-key inputs are ignored.
+    pub fn handle_input(
+        &mut self,
+        input: TerminalInput,
+        timestamp: Instant,
+    ) -> Option<KeymapEvent<A>> {
+        let TerminalInput::Key(key) = input else {
+            return None;
+        };
+        Some(self.handle_key(key, timestamp))
+    }
+
+    /// Processes a key pressed at `timestamp` and returns the result.
+    ///
+    /// If the pending key sequence has timed out but [`Keymap::handle_timeout()`]
+    /// was not called, the sequence is silently abandoned before processing `key`.
+    pub fn handle_key(&mut self, key: KeyInput, timestamp: Instant) -> KeymapEvent<A> {
+        if self
+            .next_deadline()
+            .is_some_and(|deadline| deadline <= timestamp)
+        {
+            self.cancel_pending();
+        }
+
+        self.pending.push(normalize_key(key));
+        let action = match self.lookup(&self.pending) {
+            Lookup::Exact(action) => action.clone(),
+            Lookup::Prefix => {
+                self.last_key_time = Some(timestamp);
+                return KeymapEvent::Pending;
+            }
+            Lookup::None => {
+                self.last_key_time = None;
+                return KeymapEvent::Unbound(std::mem::take(&mut self.pending));
+            }
+        };
+        self.cancel_pending();
+        KeymapEvent::Action(action)
+    }
+}
+
+impl<A> Default for Keymap<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum Lookup<'a, A> {
+    Exact(&'a A),
+    Prefix,
+    None,
+}
+
+fn lookup<'a, A>(bindings: &'a BTreeMap<Vec<KeyInput>, A>, keys: &[KeyInput]) -> Lookup<'a, A> {
+    // Sequences starting with `keys` immediately follow `keys` itself in the sorted map
+    match bindings.range(keys.to_vec()..).next() {
+        Some((k, action)) if k == keys => Lookup::Exact(action),
+        Some((k, _)) if k.starts_with(keys) => Lookup::Prefix,
+        _ => Lookup::None,
+    }
+}
+
+fn parse_key_sequence(s: &str) -> std::io::Result<Vec<KeyInput>> {
+    let keys = s
+        .split_whitespace()
+        .map(parse_key)
+        .collect::<std::io::Result<Vec<_>>>()?;
+    if keys.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidInput, "empty key sequence"));
+    }
+    Ok(keys)
+}
+
+fn parse_key(s: &str) -> std::io::Result<KeyInput> {
+    let error = || Error::new(ErrorKind::InvalidInput, format!("invalid key: {s:?}"));
+
+    // Modifiers may be written both outside and inside the angle brackets (e.g., `M-<C-x>`)
+    let (outer, rest) = strip_modifiers(s);
+    let rest = rest
+        .strip_prefix('<')
+        .and_then(|r| r.strip_suffix('>'))
+        .filter(|r| !r.is_empty())
+        .unwrap_or(rest);
+    let (inner, name) = strip_modifiers(rest);
+    let ctrl = outer.ctrl || inner.ctrl;
+    let alt = outer.alt || inner.alt;
+    let code = match name.to_ascii_lowercase().as_str() {
+        "lt" => KeyCode::Char('<'),
+        "gt" => KeyCode::Char('>'),
+        _ => name.parse().map_err(|_| error())?,
+    };
+    // Shift is only meaningful for Tab, as [`KeyInput`] has no Shift flag
+    let code = match (outer.shift || inner.shift, code) {
+        (true, KeyCode::Tab) => KeyCode::BackTab,
+        (true, _) => return Err(error()),
+        (false, code) => code,
+    };
+    Ok(KeyInput { ctrl, alt, code })
+}
+
+/// Folds keys that the legacy input decoder cannot tell apart, as it receives the same byte
+/// for them, into the form it produces.
+///
+/// With `modifyOtherKeys`, terminals report, e.g., Ctrl+I as `Ctrl+Char('i')` rather than Tab,
+/// so the folding is applied to both bindings and pressed keys.
+fn normalize_key(key: KeyInput) -> KeyInput {
+    let (ctrl, code) = match (key.ctrl, key.code) {
+        (true, KeyCode::Char('i')) => (false, KeyCode::Tab),
+        (true, KeyCode::Char('m')) => (false, KeyCode::Enter),
+        (true, KeyCode::Char('[')) => (false, KeyCode::Escape),
+        (true, KeyCode::Char('@')) => (true, KeyCode::Char(' ')),
+        (ctrl, code) => (ctrl, code),
+    };
+    KeyInput { ctrl, code, ..key }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(ctrl: bool, alt: bool, code: KeyCode) -> KeyInput {
+        KeyInput { ctrl, alt, code }
+    }
+
+    fn ch(c: char) -> KeyInput {
+        key(false, false, KeyCode::Char(c))
+    }
+
+    fn ctrl(c: char) -> KeyInput {
+        key(true, false, KeyCode::Char(c))
+    }
+
+    #[test]
+    fn parse_keys() {
+        assert_eq!(
+            parse_key_sequence("C-x C-s").expect("ok"),
+            [ctrl('x'), ctrl('s')]
+        );
+        assert_eq!(parse_key_sequence("g  g").expect("ok"), [ch('g'), ch('g')]);
+        assert_eq!(
+            parse_key_sequence("M-<Up> <f5> <C-M-pagedown> Ctrl+Alt+Delete").expect("ok"),
+            [
+                key(false, true, KeyCode::Up),
+                key(false, false, KeyCode::F(5)),
+                key(true, true, KeyCode::PageDown),
+                key(true, true, KeyCode::Delete),
+            ]
+        );
+        assert_eq!(
+            parse_key_sequence("C-<Space> - < <lt> <Space> C-X C-i C-[ C-@ C-\\")
+                .expect("ok")
+                .into_iter()
+                .map(normalize_key)
+                .collect::<Vec<_>>(),
+            [
+                ctrl(' '),
+                ch('-'),
+                ch('<'),
+                ch('<'),
+                ch(' '),
+                ctrl('X'),
+                key(false, false, KeyCode::Tab),
+                key(false, false, KeyCode::Escape),
+                ctrl(' '),
+                ctrl('\\'),
+            ]
+        );
+
+        assert_eq!(
+            parse_key_sequence("<S-Tab> S-Tab <s-tab> <CR> M-cr").expect("ok"),
+            [
+                key(false, false, KeyCode::BackTab),
+                key(false, false, KeyCode::BackTab),
+                key(false, false, KeyCode::BackTab),
+                key(false, false, KeyCode::Enter),
+                key(false, true, KeyCode::Enter),
+            ]
+        );
+
+        assert!(parse_key_sequence("").is_err());
+        assert!(parse_key_sequence("<Foo>").is_err());
+        assert!(parse_key_sequence("<F0>").is_err());
+        assert!(parse_key_sequence("<F256>").is_err());
+        assert!(parse_key_sequence("S-a").is_err());
+        assert!(parse_key_sequence("ab").is_err());
+    }
+
+    #[test]
+    fn chords() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let mut keymap = Keymap::new();
+        keymap.bind("normal", "C-x C-s", "save").expect("ok");
+        keymap.bind("normal", "C-x C-c", "quit").expect("ok");
+        keymap.bind("normal", "g g", "top").expect("ok");
+
+        // Conflicting prefixes
+        assert!(keymap.bind("normal", "C-x", "x").is_err());
+        assert!(keymap.bind("normal", "g g g", "x").is_err());
+        // Rebinding replaces the action
+        keymap.bind("normal", "g g", "first").expect("ok");
+
+        assert_eq!(keymap.handle_key(ctrl('x'), t0), KeymapEvent::Pending);
+        assert_eq!(keymap.pending_keys(), [ctrl('x')]);
+        assert_eq!(keymap.next_deadline(), Some(t0 + ms(1000)));
+        assert_eq!(
+            keymap.handle_key(ctrl('s'), t0 + ms(10)),
+            KeymapEvent::Action("save")
+        );
+        assert_eq!(keymap.next_deadline(), None);
+
+        // Unbound keys
+        assert_eq!(
+            keymap.handle_key(ch('a'), t0),
+            KeymapEvent::Unbound(vec![ch('a')])
+        );
+        assert_eq!(keymap.handle_key(ch('g'), t0), KeymapEvent::Pending);
+        assert_eq!(
+            keymap.handle_key(ch('x'), t0),
+            KeymapEvent::Unbound(vec![ch('g'), ch('x')])
+        );
+
+        // Timeout
+        assert_eq!(keymap.handle_key(ch('g'), t0), KeymapEvent::Pending);
+        assert_eq!(keymap.handle_timeout(t0 + ms(999)), None);
+        assert_eq!(
+            keymap.handle_timeout(t0 + ms(1000)),
+            Some(KeymapEvent::Unbound(vec![ch('g')]))
+        );
+        assert_eq!(keymap.handle_timeout(t0 + ms(2000)), None);
+
+        // Timed out without `handle_timeout()`
+        keymap.set_chord_timeout(ms(100));
+        assert_eq!(keymap.handle_key(ch('g'), t0), KeymapEvent::Pending);
+        assert_eq!(
+            keymap.handle_key(ch('g'), t0 + ms(150)),
+            KeymapEvent::Pending
+        );
+        assert_eq!(
+            keymap.handle_key(ch('g'), t0 + ms(200)),
+            KeymapEvent::Action("first")
+        );
+    }
+
+    #[test]
+    fn modes() {
+        let t0 = Instant::now();
+        let mut keymap = Keymap::new();
+        keymap.bind(GLOBAL_MODE, "C-c", "quit").expect("ok");
+        keymap.bind(GLOBAL_MODE, "<F1>", "help").expect("ok");
+        keymap.bind("normal", "i", "insert").expect("ok");
+        keymap.bind("insert", "<Esc>", "normal").expect("ok");
+        keymap.bind("insert", "C-c", "normal").expect("ok");
+
+        assert_eq!(keymap.mode(), "normal");
+        assert_eq!(
+            keymap.handle_key(ch('i'), t0),
+            KeymapEvent::Action("insert")
+        );
+        assert_eq!(
+            keymap.handle_key(ctrl('c'), t0),
+            KeymapEvent::Action("quit")
+        );
+
+        keymap.set_mode("insert");
+        assert_eq!(
+            keymap.handle_key(ch('i'), t0),
+            KeymapEvent::Unbound(vec![ch('i')])
+        );
+        // The mode's own binding takes precedence over the global one
+        assert_eq!(
+            keymap.handle_key(ctrl('c'), t0),
+            KeymapEvent::Action("normal")
+        );
+        assert_eq!(
+            keymap.handle_key(key(false, false, KeyCode::F(1)), t0),
+            KeymapEvent::Action("help")
+        );
+    }
+
+    #[test]
+    fn modify_other_keys() {
+        let t0 = Instant::now();
+        let mut keymap = Keymap::new();
+        keymap.bind("normal", "C-X", "upper").expect("ok");
+        keymap.bind("normal", "C-x", "lower").expect("ok");
+        keymap.bind("normal", "C-i", "tab").expect("ok");
+
+        // Ctrl+Shift+X, Ctrl+X and Ctrl+I with modifyOtherKeys, followed by the legacy bytes
+        let mut parser = crate::InputParser::new();
+        parser.feed(b"\x1b[27;6;120~\x1b[27;5;120~\x1b[27;5;105~\x18\t");
+        let mut actions = Vec::new();
+        while let Some(input) = parser.next_input() {
+            let event = keymap.handle_input(input, t0);
+            actions.push(event.expect("key input"));
+        }
+        assert_eq!(
+            actions,
+            [
+                KeymapEvent::Action("upper"),
+                KeymapEvent::Action("lower"),
+                KeymapEvent::Action("tab"),
+                KeymapEvent::Action("lower"),
+                KeymapEvent::Action("tab"),
+            ]
+        );
+    }
+
+    #[test]
+    fn load() {
+        let mut keymap = Keymap::<String>::new();
+        keymap
+            .load(
+                "# comment\n\
+                 C-x C-c = quit\n\
+                 \n\
+                 [normal]\n\
+                 g g = goto-top\n\
+                 C-= = zoom-in\n\
+                 [insert]\n\
+                 <Esc> = normal-mode\n",
+            )
+            .expect("ok");
+
+        let t0 = Instant::now();
+        assert_eq!(keymap.handle_key(ch('g'), t0), KeymapEvent::Pending);
+        assert_eq!(
+            keymap.handle_key(ch('g'), t0),
+            KeymapEvent::Action("goto-top".to_owned())
+        );
+        assert_eq!(
+            keymap.handle_key(ctrl('='), t0),
+            KeymapEvent::Action("zoom-in".to_owned())
+        );
+        keymap.set_mode("insert");
+        assert_eq!(
+            keymap.handle_key(key(false, false, KeyCode::Escape), t0),
+            KeymapEvent::Action("normal-mode".to_owned())
+        );
+        assert_eq!(keymap.handle_key(ctrl('x'), t0), KeymapEvent::Pending);
+        assert_eq!(
+            keymap.handle_key(ctrl('c'), t0),
+            KeymapEvent::Action("quit".to_owned())
+        );
+
+        let e = keymap.load("a = b\nfoo\n").expect_err("error");
+        assert_eq!(e.kind(), ErrorKind::InvalidInput);
+        assert!(e.to_string().starts_with("line 2:"));
+        let e = keymap.load("\n\n<Bar> = b\n").expect_err("error");
+        assert!(e.to_string().starts_with("line 3:"));
+
+        let mut keymap = Keymap::<u32>::new();
+        let e = keymap.load("a = b\n").expect_err("error");
+        assert!(e.to_string().starts_with("line 1: invalid action"));
+    }
+}
@@ -83,17 +83,32 @@
 #![warn(missing_docs)]
 use std::{io::ErrorKind, os::fd::RawFd};
 
+mod base64;
+mod encoder;
 mod frame;
 mod geometry;
+mod gesture;
 mod input;
+mod keymap;
+mod output;
 mod style;
 mod terminal;
+mod terminfo;
 
+pub use encoder::{InputEncoder, MouseEncoding};
 pub use frame::{EstimateCharWidth, FixedCharWidthEstimator, TerminalFrame};
-pub use geometry::{TerminalPosition, TerminalRegion, TerminalSize};
-pub use input::{KeyCode, KeyInput, MouseEvent, MouseInput, TerminalInput};
-pub use style::{TerminalColor, TerminalStyle};
-pub use terminal::{Terminal, TerminalEvent};
+pub use geometry::{PixelPosition, PixelSize, TerminalPosition, TerminalRegion, TerminalSize};
+pub use gesture::{GestureConfig, GestureEvent, GestureRecognizer};
+pub use input::{
+    InputDialect, InputParser, KeyCode, KeyInput, MouseButton, MouseEvent, MouseInput,
+    TerminalInput,
+};
+pub use keymap::{DEFAULT_MODE, GLOBAL_MODE, Keymap, KeymapEvent};
+pub use style::{CursorStyle, TerminalColor, TerminalHyperlink, TerminalStyle};
+pub use terminal::{
+    ClipboardSelection, CoalescedEvent, MouseTracking, RawModeConfig, RenderStats, Terminal,
+    TerminalEvent, TimedEvent,
+};
 
 /// Sets a file descriptor to non-blocking mode.
 ///
@@ -0,0 +1,285 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Error, ErrorKind, Stdout, Write},
+    mem::MaybeUninit,
+    os::{
+        fd::{AsRawFd, RawFd},
+        unix::fs::OpenOptionsExt,
+    },
+};
+
+/// Default limit of the bytes queued in non-blocking mode.
+pub const DEFAULT_MAX_PENDING_BYTES: usize = 1024 * 1024;
+
+/// Buffered terminal output that can be flushed incrementally when the fd is non-blocking.
+///
+/// The bytes are written directly to the fd of `inner`, or in non-blocking mode,
+/// to a separately opened fd of the same terminal if available.
+#[derive(Debug)]
+pub struct OutputWriter<T = Stdout> {
+    inner: T,
+    tty: Option<File>,
+    buf: Vec<u8>,
+    total_bytes: u64,
+    nonblocking: bool,
+    max_pending_bytes: usize,
+}
+
+impl<T: AsRawFd> OutputWriter<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            tty: None,
+            buf: Vec::new(),
+            total_bytes: 0,
+            nonblocking: false,
+            max_pending_bytes: DEFAULT_MAX_PENDING_BYTES,
+        }
+    }
+
+    pub fn fd(&self) -> RawFd {
+        self.tty
+            .as_ref()
+            .map_or_else(|| self.inner.as_raw_fd(), |tty| tty.as_raw_fd())
+    }
+
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking
+    }
+
+    /// Enables or disables non-blocking mode.
+    ///
+    /// The non-blocking flag belongs to the open file description, which `inner` often
+    /// shares with the terminal input (e.g., when both are inherited from the shell).
+    /// To leave the input blocking, `/dev/tty` is opened anew with the flag if it refers to
+    /// the same terminal as `inner`. Otherwise, the flag is set on `inner` itself.
+    pub fn set_nonblocking(&mut self, nonblocking: bool) -> std::io::Result<()> {
+        if self.nonblocking == nonblocking {
+            return Ok(());
+        }
+
+        if nonblocking {
+            self.tty = open_same_tty(self.inner.as_raw_fd());
+        }
+        if self.tty.is_none() {
+            set_fd_nonblocking(self.inner.as_raw_fd(), nonblocking)?;
+        }
+        if !nonblocking {
+            self.tty = None;
+        }
+        self.nonblocking = nonblocking;
+        Ok(())
+    }
+
+    /// Sets the maximum number of bytes kept queued in non-blocking mode.
+    ///
+    /// A flush that would leave more bytes queued waits until the terminal accepts enough
+    /// of them, so the queue cannot grow without bound when the terminal stops reading.
+    pub fn set_max_pending_bytes(&mut self, n: usize) {
+        self.max_pending_bytes = n;
+    }
+
+    /// Returns the number of bytes written to this writer so far, whether flushed or not.
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    pub fn pending_bytes(&self) -> usize {
+        self.buf.len()
+    }
+
+    fn wait_writable(&self) -> std::io::Result<()> {
+        let mut pollfd = libc::pollfd {
+            fd: self.fd(),
+            events: libc::POLLOUT,
+            revents: 0,
+        };
+        if unsafe { libc::poll(&mut pollfd, 1, -1) } < 0 {
+            let e = Error::last_os_error();
+            if e.kind() != ErrorKind::Interrupted {
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: AsRawFd> Write for OutputWriter<T> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        self.total_bytes += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    /// Writes the buffered bytes to the terminal.
+    ///
+    /// In non-blocking mode, this stops as soon as the terminal cannot accept more bytes
+    /// and keeps the rest in the buffer.
+    fn flush(&mut self) -> std::io::Result<()> {
+        let mut written = 0;
+        let result = loop {
+            if written == self.buf.len() {
+                break Ok(());
+            }
+
+            let remaining = &self.buf[written..];
+            let n = unsafe { libc::write(self.fd(), remaining.as_ptr().cast(), remaining.len()) };
+            if n >= 0 {
+                written += n as usize;
+                continue;
+            }
+
+            let e = Error::last_os_error();
+            match e.kind() {
+                ErrorKind::Interrupted => {}
+                ErrorKind::WouldBlock
+                    if self.nonblocking && remaining.len() <= self.max_pending_bytes =>
+                {
+                    break Ok(());
+                }
+                ErrorKind::WouldBlock => {
+                    // The queue is over the limit, or the fd has been made non-blocking
+                    // by someone else (e.g., via a shared file description with stdin)
+                    if let Err(e) = self.wait_writable() {
+                        break Err(e);
+                    }
+                }
+                _ => break Err(e),
+            }
+        };
+        self.buf.drain(..written);
+        result
+    }
+}
+
+fn set_fd_nonblocking(fd: RawFd, nonblocking: bool) -> std::io::Result<()> {
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL, 0);
+        if flags < 0 {
+            return Err(Error::last_os_error());
+        }
+        let flags = if nonblocking {
+            flags | libc::O_NONBLOCK
+        } else {
+            flags & !libc::O_NONBLOCK
+        };
+        if libc::fcntl(fd, libc::F_SETFL, flags) < 0 {
+            return Err(Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Opens the controlling terminal for non-blocking writes if `fd` refers to it.
+fn open_same_tty(fd: RawFd) -> Option<File> {
+    let device = |fd: RawFd| {
+        let mut stat = MaybeUninit::<libc::stat>::zeroed();
+        if unsafe { libc::fstat(fd, stat.as_mut_ptr()) } < 0 {
+            return None;
+        }
+        let stat = unsafe { stat.assume_init() };
+        (stat.st_mode & libc::S_IFMT == libc::S_IFCHR).then_some(stat.st_rdev)
+    };
+
+    let tty = OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NOCTTY | libc::O_NONBLOCK)
+        .open("/dev/tty")
+        .ok()?;
+    (device(fd)? == device(tty.as_raw_fd())?).then_some(tty)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, os::unix::net::UnixStream};
+
+    use super::*;
+
+    fn read_available(stream: &mut UnixStream, received: &mut Vec<u8>) {
+        let mut buf = [0; 64 * 1024];
+        loop {
+            match stream.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => received.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => panic!("{e}"),
+            }
+        }
+    }
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn partial_writes() {
+        let (stream, mut peer) = UnixStream::pair().expect("ok");
+        peer.set_nonblocking(true).expect("ok");
+        let mut output = OutputWriter::new(stream);
+        output.set_nonblocking(true).expect("ok");
+        assert!(output.is_nonblocking());
+
+        // The socket accepts only part of the bytes, and the rest is kept queued
+        let data = data(1024 * 1024);
+        output.write_all(&data).expect("ok");
+        output.flush().expect("ok");
+        let pending = output.pending_bytes();
+        assert!(0 < pending && pending < data.len());
+        assert_eq!(output.total_bytes(), data.len() as u64);
+
+        let mut received = Vec::new();
+        read_available(&mut peer, &mut received);
+        assert_eq!(received, data[..data.len() - pending]);
+
+        // The queue drains in order as the peer reads
+        output.write_all(b"tail").expect("ok");
+        while output.pending_bytes() > 0 {
+            output.flush().expect("ok");
+            read_available(&mut peer, &mut received);
+        }
+        read_available(&mut peer, &mut received);
+        assert_eq!(received.len(), data.len() + 4);
+        assert_eq!(received[..data.len()], data);
+        assert!(received.ends_with(b"tail"));
+
+        // Disabling non-blocking mode makes flushes wait for the peer
+        output.set_nonblocking(false).expect("ok");
+        let reader = std::thread::spawn(move || {
+            let mut received = Vec::new();
+            peer.set_nonblocking(false).expect("ok");
+            peer.read_to_end(&mut received).expect("ok");
+            received
+        });
+        output.write_all(&data).expect("ok");
+        output.flush().expect("ok");
+        assert_eq!(output.pending_bytes(), 0);
+        drop(output);
+        assert_eq!(reader.join().expect("ok"), data);
+    }
+
+    #[test]
+    fn max_pending_bytes() {
+        let (stream, mut peer) = UnixStream::pair().expect("ok");
+        let mut output = OutputWriter::new(stream);
+        output.set_nonblocking(true).expect("ok");
+        output.set_max_pending_bytes(1024);
+
+        let reader = std::thread::spawn(move || {
+            let mut received = Vec::new();
+            peer.read_to_end(&mut received).expect("ok");
+            received
+        });
+
+        // A flush returns once the queue is below the limit, waiting for the peer as needed
+        let data = data(4 * 1024 * 1024);
+        for chunk in data.chunks(64 * 1024) {
+            output.write_all(chunk).expect("ok");
+            output.flush().expect("ok");
+            assert!(output.pending_bytes() <= 1024);
+        }
+        output.set_nonblocking(false).expect("ok");
+        output.flush().expect("ok");
+        drop(output);
+        assert_eq!(reader.join().expect("ok"), data);
+    }
+}
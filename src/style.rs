@@ -289,6 +289,168 @@ impl FromStr for TerminalStyle {
     }
 }
 
+/// Hyperlink (OSC 8) that can be attached to text written to a [`TerminalFrame`](crate::TerminalFrame).
+///
+/// Like [`TerminalStyle`], a hyperlink is applied by writing it to a frame.
+/// All text written after it is linked to the URI until [`TerminalHyperlink::END`]
+/// (or another hyperlink) is written. Terminals that support OSC 8 make such text clickable.
+///
+/// Adjacent cells with the same URI and ID are treated as a single link by the terminal,
+/// even when they are not contiguous (e.g., a link wrapped across several lines).
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt::Write;
+/// use tuinix::{TerminalFrame, TerminalHyperlink, TerminalSize};
+///
+/// let mut frame: TerminalFrame = TerminalFrame::new(TerminalSize::rows_cols(10, 40));
+/// let link = TerminalHyperlink::new("https://github.com/sile/tuinix")?;
+///
+/// writeln!(frame, "See {link}the repository{} for details.", TerminalHyperlink::END)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TerminalHyperlink {
+    /// Target URI of the link (empty for [`TerminalHyperlink::END`]).
+    ///
+    /// It must not contain control characters.
+    pub uri: String,
+
+    /// Optional ID used by the terminal to group cells belonging to the same link.
+    ///
+    /// It must not contain control characters, `:` or `;`.
+    pub id: Option<String>,
+}
+
+impl TerminalHyperlink {
+    /// A special value that ends the current hyperlink.
+    pub const END: Self = Self {
+        uri: String::new(),
+        id: None,
+    };
+
+    /// Makes a new hyperlink to the given URI.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidInput`](std::io::ErrorKind::InvalidInput) error if
+    /// `uri` contains control characters (e.g., ESC or BEL), which would end or corrupt
+    /// the escape sequence.
+    pub fn new(uri: impl Into<String>) -> std::io::Result<Self> {
+        let uri = uri.into();
+        if uri.contains(char::is_control) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("hyperlink URI contains control characters: {uri:?}"),
+            ));
+        }
+        Ok(Self { uri, id: None })
+    }
+
+    /// Sets the ID of the hyperlink.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidInput`](std::io::ErrorKind::InvalidInput) error if
+    /// `id` contains control characters, `:` or `;`.
+    pub fn id(mut self, id: impl Into<String>) -> std::io::Result<Self> {
+        let id = id.into();
+        if id.contains(|c: char| c.is_control() || c == ':' || c == ';') {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid hyperlink ID: {id:?}"),
+            ));
+        }
+        self.id = Some(id);
+        Ok(self)
+    }
+}
+
+impl Display for TerminalHyperlink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\x1b]8;")?;
+        if let Some(id) = &self.id {
+            write!(f, "id={id}")?;
+        }
+        write!(f, ";{}\x1b\\", self.uri)
+    }
+}
+
+impl FromStr for TerminalHyperlink {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("invalid or unsupported OSC 8 escape sequence: {s:?}");
+
+        let s = s.strip_prefix("\x1b]8;").ok_or_else(error)?;
+        let s = s
+            .strip_suffix("\x1b\\")
+            .or_else(|| s.strip_suffix('\x07'))
+            .ok_or_else(error)?;
+        let (params, uri) = s.split_once(';').ok_or_else(error)?;
+        let id = params
+            .split(':')
+            .find_map(|param| param.strip_prefix("id="))
+            .map(|id| id.to_owned());
+        Ok(Self {
+            uri: uri.to_owned(),
+            id,
+        })
+    }
+}
+
+/// Cursor shape and blinking behavior, set via the DECSCUSR escape sequence.
+///
+/// Use [`Terminal::set_cursor_style()`](crate::Terminal::set_cursor_style) to apply a style.
+///
+/// # Examples
+///
+/// ```
+/// use tuinix::CursorStyle;
+///
+/// assert_eq!(CursorStyle::SteadyBar.to_string(), "\x1b[6 q");
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CursorStyle {
+    /// The terminal's default cursor style.
+    #[default]
+    Default,
+
+    /// Blinking block cursor.
+    BlinkingBlock,
+
+    /// Steady (non-blinking) block cursor.
+    SteadyBlock,
+
+    /// Blinking underline cursor.
+    BlinkingUnderline,
+
+    /// Steady (non-blinking) underline cursor.
+    SteadyUnderline,
+
+    /// Blinking vertical bar cursor.
+    BlinkingBar,
+
+    /// Steady (non-blinking) vertical bar cursor.
+    SteadyBar,
+}
+
+impl Display for CursorStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let n = match self {
+            Self::Default => 0,
+            Self::BlinkingBlock => 1,
+            Self::SteadyBlock => 2,
+            Self::BlinkingUnderline => 3,
+            Self::SteadyUnderline => 4,
+            Self::BlinkingBar => 5,
+            Self::SteadyBar => 6,
+        };
+        write!(f, "\x1b[{n} q")
+    }
+}
+
 /// Terminal color (RGB).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TerminalColor {
@@ -355,6 +517,35 @@ impl TerminalColor {
     pub const fn new(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b }
     }
+
+    /// Parses an X11 color specification of the form `rgb:<red>/<green>/<blue>`,
+    /// where each component has one to four hex digits (e.g., `rgb:ffff/8080/0000`).
+    ///
+    /// This is the format terminals use to reply to OSC 4/10/11 color queries.
+    pub(crate) fn from_x11_rgb(s: &str) -> Option<Self> {
+        let parse_component = |s: &str| {
+            if !(1..=4).contains(&s.len()) || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
+            }
+            let value = u32::from_str_radix(s, 16).ok()?;
+            let max = (1u32 << (s.len() * 4)) - 1;
+            Some(((value * 255 + max / 2) / max) as u8)
+        };
+
+        let mut components = s.strip_prefix("rgb:")?.split('/');
+        let r = parse_component(components.next()?)?;
+        let g = parse_component(components.next()?)?;
+        let b = parse_component(components.next()?)?;
+        if components.next().is_some() {
+            return None;
+        }
+        Some(Self::new(r, g, b))
+    }
+
+    /// Formats this color as an X11 color specification (`rgb:rr/gg/bb`).
+    pub(crate) fn to_x11_rgb(self) -> String {
+        format!("rgb:{:02x}/{:02x}/{:02x}", self.r, self.g, self.b)
+    }
 }
 
 #[cfg(test)]
@@ -373,4 +564,65 @@ mod tests {
         assert_eq!(style.fg_color, Some(TerminalColor::BLACK));
         assert_eq!(style.bg_color, Some(TerminalColor::YELLOW));
     }
+
+    #[test]
+    fn parse_hyperlink() {
+        let link = TerminalHyperlink::new("https://example.com/a;b")
+            .and_then(|link| link.id("x"))
+            .expect("valid");
+        assert_eq!(link.to_string().parse(), Ok(link));
+
+        assert!(TerminalHyperlink::new("https://example.com/\x1b\\").is_err());
+        assert!(TerminalHyperlink::new("https://example.com/\x07").is_err());
+        let link = TerminalHyperlink::new("https://example.com").expect("valid");
+        assert!(link.clone().id("a:b").is_err());
+        assert!(link.clone().id("a;b").is_err());
+        assert!(link.id("a\x1b").is_err());
+
+        let end: TerminalHyperlink = "\x1b]8;;\x07".parse().expect("invalid");
+        assert_eq!(end, TerminalHyperlink::END);
+        assert!(
+            "\x1b]8;;https://example.com"
+                .parse::<TerminalHyperlink>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn cursor_style_sequences() {
+        let styles = [
+            CursorStyle::Default,
+            CursorStyle::BlinkingBlock,
+            CursorStyle::SteadyBlock,
+            CursorStyle::BlinkingUnderline,
+            CursorStyle::SteadyUnderline,
+            CursorStyle::BlinkingBar,
+            CursorStyle::SteadyBar,
+        ];
+        for (n, style) in styles.into_iter().enumerate() {
+            assert_eq!(style.to_string(), format!("\x1b[{n} q"));
+        }
+        assert_eq!(CursorStyle::default(), CursorStyle::Default);
+    }
+
+    #[test]
+    fn parse_x11_rgb() {
+        assert_eq!(
+            TerminalColor::from_x11_rgb("rgb:ffff/8080/0000"),
+            Some(TerminalColor::new(255, 128, 0))
+        );
+        assert_eq!(
+            TerminalColor::from_x11_rgb("rgb:f/80/000"),
+            Some(TerminalColor::new(255, 128, 0))
+        );
+        assert_eq!(TerminalColor::from_x11_rgb("rgb:ff/ff"), None);
+        assert_eq!(TerminalColor::from_x11_rgb("rgb:fffff/0/0"), None);
+        assert_eq!(TerminalColor::from_x11_rgb("#ff8000"), None);
+
+        let color = TerminalColor::new(1, 2, 255);
+        assert_eq!(
+            TerminalColor::from_x11_rgb(&color.to_x11_rgb()),
+            Some(color)
+        );
+    }
 }
@@ -1,21 +1,38 @@
 use std::{
     fs::File,
-    io::{BufWriter, Error, ErrorKind, IsTerminal, Read, Stdout, Write},
+    io::{Error, ErrorKind, IsTerminal, Read, Write},
     mem::MaybeUninit,
     os::fd::{AsRawFd, FromRawFd, RawFd},
-    sync::atomic::{AtomicBool, Ordering},
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
     time::{Duration, Instant},
 };
 
 use crate::{
-    TerminalFrame, TerminalPosition, TerminalSize,
-    input::{InputReader, TerminalInput},
+    CursorStyle, MouseEvent, PixelSize, TerminalColor, TerminalFrame, TerminalHyperlink,
+    TerminalPosition, TerminalSize, base64,
+    input::{InputDialect, InputReader, TerminalInput, TerminalResponse},
+    output::OutputWriter,
 };
 
 static TERMINAL_EXISTS: AtomicBool = AtomicBool::new(false);
 
 static mut SIGWINCH_PIPE_FD: RawFd = 0;
 
+/// Bit set of terminal settings changed by the application that must be restored
+/// on drop or panic.
+static RESTORE_FLAGS: AtomicU32 = AtomicU32::new(0);
+
+const RESTORE_CURSOR_STYLE: u32 = 1 << 0;
+const RESTORE_CURSOR_COLOR: u32 = 1 << 1;
+const RESTORE_POINTER_SHAPE: u32 = 1 << 2;
+const RESTORE_TITLE: u32 = 1 << 3;
+const RESTORE_FOREGROUND_COLOR: u32 = 1 << 4;
+const RESTORE_BACKGROUND_COLOR: u32 = 1 << 5;
+const RESTORE_PALETTE: u32 = 1 << 6;
+const RESTORE_APPLICATION_CURSOR: u32 = 1 << 7;
+const RESTORE_APPLICATION_KEYPAD: u32 = 1 << 8;
+const RESTORE_MODIFY_OTHER_KEYS: u32 = 1 << 9;
+
 /// Terminal interface for building TUI (Terminal User Interface) applications.
 ///
 /// The [`Terminal`] struct provides a foundational layer for creating terminal-based
@@ -25,7 +42,7 @@ static mut SIGWINCH_PIPE_FD: RawFd = 0;
 /// - Alternate screen buffer
 /// - Terminal size detection and window resize events
 /// - Input event handling
-/// - Cursor positioning and visibility
+/// - Cursor positioning, visibility and style
 /// - Drawing frames with styled characters
 ///
 /// Only one instance of [`Terminal`] can exist at a time, ensuring proper management
@@ -132,12 +149,13 @@ static mut SIGWINCH_PIPE_FD: RawFd = 0;
 /// ```
 pub struct Terminal {
     input: InputReader<File>,
-    output: BufWriter<Stdout>,
+    output: OutputWriter,
     signal: File,
     original_termios: libc::termios,
+    raw_mode_config: RawModeConfig,
     size: TerminalSize,
-    last_frame: TerminalFrame,
-    cursor: Option<TerminalPosition>,
+    cell_pixel_size: PixelSize,
+    renderer: Renderer,
 }
 
 impl Terminal {
@@ -163,6 +181,26 @@ impl Terminal {
     /// - Standard output is not a terminal
     /// - Terminal configuration fails
     pub fn new() -> std::io::Result<Self> {
+        Self::with_raw_mode_config(RawModeConfig::new())
+    }
+
+    /// Creates a new terminal interface like [`Terminal::new()`], but with customized
+    /// raw-mode settings.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tuinix::{RawModeConfig, Terminal};
+    ///
+    /// // Let Ctrl-C raise SIGINT as usual
+    /// let terminal = Terminal::with_raw_mode_config(RawModeConfig::new().signals())?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`Terminal::new()`].
+    pub fn with_raw_mode_config(config: RawModeConfig) -> std::io::Result<Self> {
         if TERMINAL_EXISTS.swap(true, Ordering::SeqCst) {
             return Err(Error::other("Terminal instance already exists"));
         }
@@ -183,13 +221,17 @@ impl Terminal {
         let stdin = unsafe { File::from_raw_fd(stdin.as_raw_fd()) };
         let mut this = Self {
             input: InputReader::new(stdin),
-            output: BufWriter::new(stdout),
+            output: OutputWriter::new(stdout),
             signal: set_sigwinch_handler()?,
             original_termios,
+            raw_mode_config: config,
             size: TerminalSize::EMPTY,
-            last_frame: TerminalFrame::default(),
-            cursor: None,
+            cell_pixel_size: PixelSize::EMPTY,
+            renderer: Renderer::default(),
         };
+        this.input
+            .parser_mut()
+            .set_dialect(InputDialect::from_env());
         this.update_size()?;
         this.enable_raw_mode()?;
         this.enable_alternate_screen()?;
@@ -202,8 +244,15 @@ impl Terminal {
             let mut stdout = std::io::stdout();
             let stdin = std::io::stdin();
             unsafe {
+                // Clear the flag `set_nonblocking_output()` may have set first, so that the following
+                // writes are not cut short and the shell is not left with a non-blocking tty
+                let flags = libc::fcntl(stdout.as_raw_fd(), libc::F_GETFL, 0);
+                if flags >= 0 && flags & libc::O_NONBLOCK != 0 {
+                    libc::fcntl(stdout.as_raw_fd(), libc::F_SETFL, flags & !libc::O_NONBLOCK);
+                }
                 libc::tcsetattr(stdin.as_raw_fd(), libc::TCSAFLUSH, &original_termios);
             }
+            let _ = write_restore_sequences(&mut stdout);
             let _ = write!(stdout, "\x1b[?1049l");
             let _ = stdout.flush();
 
@@ -222,14 +271,50 @@ impl Terminal {
         self.size
     }
 
+    /// Returns the size of a character cell in pixels, or [`PixelSize::EMPTY`] if unknown.
+    ///
+    /// The size is obtained from the kernel along with the terminal size, but not all
+    /// terminals report it. In that case, [`Terminal::query_cell_pixel_size()`] can be
+    /// used to ask the terminal directly.
+    pub fn cell_pixel_size(&self) -> PixelSize {
+        self.cell_pixel_size
+    }
+
+    /// Queries the size of a character cell in pixels (`CSI 16 t`) and waits for the reply.
+    ///
+    /// On success, the size is also returned by subsequent calls to
+    /// [`Terminal::cell_pixel_size()`] and used to derive cell positions in
+    /// SGR-pixels mouse mode (see [`Terminal::set_pixel_mouse_input()`]).
+    /// Returns `Ok(None)` if the terminal does not reply within `timeout`.
+    pub fn query_cell_pixel_size(
+        &mut self,
+        timeout: Duration,
+    ) -> std::io::Result<Option<PixelSize>> {
+        write!(self.output, "\x1b[16t")?;
+        self.output.flush()?;
+
+        let size = self.wait_for_response(timeout, |response| match response {
+            TerminalResponse::CellPixelSize(size) if !size.is_empty() => Some(*size),
+            _ => None,
+        })?;
+        if let Some(size) = size {
+            self.cell_pixel_size = size;
+            self.input.parser_mut().set_cell_pixel_size(size);
+        }
+        Ok(size)
+    }
+
     /// Returns the file descriptor of the terminal input.
     pub fn input_fd(&self) -> RawFd {
         self.input.inner().as_raw_fd()
     }
 
     /// Returns the file descriptor of the terminal output.
+    ///
+    /// This differs from the fd of standard output while non-blocking output is enabled
+    /// (see [`Terminal::set_nonblocking_output()`]).
     pub fn output_fd(&self) -> RawFd {
-        self.output.get_ref().as_raw_fd()
+        self.output.fd()
     }
 
     /// Returns the file descriptor that receives terminal resize signal notifications.
@@ -237,11 +322,235 @@ impl Terminal {
         self.signal.as_raw_fd()
     }
 
+    /// Sets the window title (OSC 2).
+    ///
+    /// On first use, the current window title and icon name are saved on the terminal's
+    /// title stack (`CSI 22 t`), and they are restored (`CSI 23 t`) when the [`Terminal`]
+    /// is dropped or the program panics, so the user's original title comes back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidInput`] error if `title` contains control characters.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tuinix::Terminal;
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// terminal.set_title("ops-console: production")?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn set_title(&mut self, title: &str) -> std::io::Result<()> {
+        write_title(&mut self.output, 2, title)?;
+        self.output.flush()
+    }
+
+    /// Sets the icon name (OSC 1), which some terminals show in tabs or taskbars.
+    ///
+    /// Like [`Terminal::set_title()`], the original icon name is restored when the
+    /// [`Terminal`] is dropped or the program panics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidInput`] error if `name` contains control characters.
+    pub fn set_icon_name(&mut self, name: &str) -> std::io::Result<()> {
+        write_title(&mut self.output, 1, name)?;
+        self.output.flush()
+    }
+
+    /// Copies text to the system clipboard via OSC 52.
+    ///
+    /// This works even when the application runs on a remote host over SSH,
+    /// as long as the local terminal supports (and allows) OSC 52.
+    /// Inside tmux or GNU screen, the sequence is wrapped for passthrough
+    /// to the outer terminal.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tuinix::{ClipboardSelection, Terminal};
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// terminal.copy_to_clipboard("Hello, clipboard!", ClipboardSelection::Clipboard)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn copy_to_clipboard(
+        &mut self,
+        text: &str,
+        selection: ClipboardSelection,
+    ) -> std::io::Result<()> {
+        let sequence = format!(
+            "\x1b]52;{};{}\x1b\\",
+            selection.as_char(),
+            base64::encode(text.as_bytes())
+        );
+        self.write_passthrough(&sequence)?;
+        self.output.flush()
+    }
+
+    /// Requests the contents of the system clipboard via OSC 52 and waits for the reply.
+    ///
+    /// Many terminals disable clipboard reading by default for security reasons,
+    /// in which case no reply arrives and this method returns `Ok(None)` after `timeout`.
+    ///
+    /// The reply is consumed from the input stream and never surfaces as [`TerminalInput`].
+    /// Inputs received while waiting are kept and returned by subsequent calls to
+    /// [`Terminal::poll_event()`] or [`Terminal::read_input()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidData`] error if the reply is not valid Base64.
+    pub fn read_clipboard(
+        &mut self,
+        selection: ClipboardSelection,
+        timeout: Duration,
+    ) -> std::io::Result<Option<String>> {
+        let sequence = format!("\x1b]52;{};?\x1b\\", selection.as_char());
+        self.write_passthrough(&sequence)?;
+        self.output.flush()?;
+
+        let Some(data) = self.wait_for_response(timeout, |response| {
+            let TerminalResponse::Osc(body) = response else {
+                return None;
+            };
+            let (_selection, data) = body.strip_prefix("52;")?.split_once(';')?;
+            Some(data.to_owned())
+        })?
+        else {
+            return Ok(None);
+        };
+        let data = base64::decode(&data)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "invalid OSC 52 reply"))?;
+        Ok(Some(String::from_utf8_lossy(&data).into_owned()))
+    }
+
+    /// Writes an escape sequence, wrapping it for tmux or GNU screen passthrough if needed.
+    fn write_passthrough(&mut self, sequence: &str) -> std::io::Result<()> {
+        if std::env::var_os("TMUX").is_some() {
+            let sequence = sequence.replace('\x1b', "\x1b\x1b");
+            write!(self.output, "\x1bPtmux;{sequence}\x1b\\")
+        } else if std::env::var("TERM").is_ok_and(|term| term.starts_with("screen")) {
+            // GNU screen limits the length of a DCS string, so send it in chunks
+            for chunk in sequence.as_bytes().chunks(76) {
+                write!(self.output, "\x1bP")?;
+                self.output.write_all(chunk)?;
+                write!(self.output, "\x1b\\")?;
+            }
+            Ok(())
+        } else {
+            write!(self.output, "{sequence}")
+        }
+    }
+
+    /// Waits until a reply accepted by `f` arrives or `timeout` elapses.
+    ///
+    /// Inputs received in the meantime are queued in the input reader.
+    fn wait_for_response<T, F>(&mut self, timeout: Duration, mut f: F) -> std::io::Result<Option<T>>
+    where
+        F: FnMut(&TerminalResponse) -> Option<T>,
+    {
+        self.input.parser_mut().set_awaiting_response(true);
+        let result = self.poll_response(timeout, &mut f);
+        self.input.parser_mut().set_awaiting_response(false);
+        result
+    }
+
+    fn poll_response<T, F>(&mut self, timeout: Duration, mut f: F) -> std::io::Result<Option<T>>
+    where
+        F: FnMut(&TerminalResponse) -> Option<T>,
+    {
+        let start_time = Instant::now();
+        loop {
+            self.input.parser_mut().queue_inputs();
+            if let Some(value) = self.input.parser_mut().take_response(&mut f) {
+                return Ok(Some(value));
+            }
+
+            let remaining = timeout.saturating_sub(start_time.elapsed());
+            if remaining.is_zero() || !wait_readable(self.input_fd(), remaining)? {
+                return Ok(None);
+            }
+            match self.input.fill_ready_buf() {
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                result => result?,
+            }
+        }
+    }
+
+    /// Enables or disables non-blocking terminal output.
+    ///
+    /// By default, [`Terminal::draw()`] and the other output methods block until all bytes
+    /// have been written, so a slow terminal (e.g., over a congested SSH connection or in a
+    /// paused tmux pane) stalls the caller.
+    ///
+    /// In non-blocking mode, bytes the terminal cannot accept yet are queued instead.
+    /// The queue is flushed incrementally by [`Terminal::poll_event()`], which watches
+    /// [`Terminal::output_fd()`] for writability while output is pending,
+    /// or explicitly by [`Terminal::flush_output()`].
+    ///
+    /// If a frame is drawn while output is still pending, it is not rendered immediately.
+    /// Only the latest such frame is kept, and it is rendered once the queue drains,
+    /// diffed against the last frame the terminal has fully received.
+    /// Intermediate frames are thus coalesced rather than piling up.
+    ///
+    /// The queue is limited to 1 MiB by default (see [`Terminal::set_max_pending_output()`]).
+    ///
+    /// As the non-blocking flag belongs to the open file description, which standard output
+    /// usually shares with standard input, the output is written through a separately opened
+    /// `/dev/tty` in this mode, so [`Terminal::input_fd()`] stays blocking.
+    /// [`Terminal::output_fd()`] returns that fd while non-blocking output is enabled.
+    /// If `/dev/tty` is not the terminal of standard output, the flag is set on standard output
+    /// itself and [`Terminal::read_input()`] may become non-blocking as well; the flag is then
+    /// cleared when the [`Terminal`] is dropped or the program panics.
+    pub fn set_nonblocking_output(&mut self, nonblocking: bool) -> std::io::Result<()> {
+        self.output.set_nonblocking(nonblocking)?;
+        if !nonblocking {
+            self.flush_output()?;
+        }
+        Ok(())
+    }
+
+    /// Sets the maximum number of output bytes queued in non-blocking mode (1 MiB by default).
+    ///
+    /// Once the queue would exceed this high-water mark, writes block until the terminal
+    /// accepts enough bytes, rather than letting the queue grow without bound
+    /// (e.g., while the terminal is suspended).
+    pub fn set_max_pending_output(&mut self, n: usize) {
+        self.output.set_max_pending_bytes(n);
+    }
+
+    /// Returns `true` if there are queued output bytes or a deferred frame that have not
+    /// been written to the terminal yet.
+    ///
+    /// This can only be `true` in non-blocking output mode
+    /// (see [`Terminal::set_nonblocking_output()`]).
+    pub fn has_pending_output(&self) -> bool {
+        self.renderer.has_pending_output(&self.output)
+    }
+
+    /// Writes as much pending output as the terminal currently accepts.
+    ///
+    /// If the queue drains and a frame was deferred in the meantime, that frame is rendered
+    /// (and flushed) as well.
+    ///
+    /// When using external I/O polling crates like `mio`, call this method whenever
+    /// [`Terminal::output_fd()`] becomes writable while [`Terminal::has_pending_output()`]
+    /// returns `true`.
+    ///
+    /// Returns `true` if all output has been written.
+    pub fn flush_output(&mut self) -> std::io::Result<bool> {
+        self.renderer.flush(&mut self.output)
+    }
+
     /// Enables mouse input reporting in the terminal.
     ///
     /// Mouse events will be received through [`Terminal::poll_event()`] or [`Terminal::read_input()`]
     /// as [`TerminalInput::Mouse`] variants.
     ///
+    /// This is equivalent to [`Terminal::enable_mouse_tracking()`] with
+    /// [`MouseTracking::ButtonMotion`].
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -265,9 +574,48 @@ impl Terminal {
     /// # Ok::<(), std::io::Error>(())
     /// ```
     pub fn enable_mouse_input(&mut self) -> std::io::Result<()> {
+        self.enable_mouse_tracking(MouseTracking::ButtonMotion)
+    }
+
+    /// Enables mouse input reporting in the terminal with the given tracking level.
+    ///
+    /// Calling this method again switches to the new tracking level.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tuinix::{MouseEvent, MouseTracking, Terminal, TerminalEvent, TerminalInput};
+    ///
+    /// let mut terminal = Terminal::new()?;
+    ///
+    /// // Report hover, e.g., for highlighting the item under the pointer
+    /// terminal.enable_mouse_tracking(MouseTracking::AnyMotion)?;
+    ///
+    /// if let Some(TerminalEvent::Input(TerminalInput::Mouse(mouse))) =
+    ///     terminal.poll_event(&[], &[], None)?
+    ///     && mouse.event == MouseEvent::Move
+    /// {
+    ///     println!("Hovering at ({}, {})", mouse.position.col, mouse.position.row);
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn enable_mouse_tracking(&mut self, tracking: MouseTracking) -> std::io::Result<()> {
         // Enable mouse reporting in SGR mode (more reliable than X10/X11 mode)
         write!(self.output, "\x1b[?1000h")?; // Enable basic mouse reporting
-        write!(self.output, "\x1b[?1002h")?; // Enable button event tracking and motion
+        match tracking {
+            MouseTracking::Clicks => {
+                write!(self.output, "\x1b[?1003l")?;
+                write!(self.output, "\x1b[?1002l")?;
+            }
+            MouseTracking::ButtonMotion => {
+                write!(self.output, "\x1b[?1003l")?;
+                write!(self.output, "\x1b[?1002h")?; // Enable button event tracking and motion
+            }
+            MouseTracking::AnyMotion => {
+                write!(self.output, "\x1b[?1002l")?;
+                write!(self.output, "\x1b[?1003h")?; // Enable all motion tracking
+            }
+        }
         write!(self.output, "\x1b[?1015h")?; // Enable urxvt extended coordinate reporting
         write!(self.output, "\x1b[?1006h")?; // Enable SGR extended coordinate reporting
         self.output.flush()?;
@@ -285,23 +633,230 @@ impl Terminal {
     /// while keeping the Terminal instance active.
     pub fn disable_mouse_input(&mut self) -> std::io::Result<()> {
         // Disable mouse reporting (reverse order)
+        self.input.parser_mut().set_pixel_mouse(false);
+        self.input.parser_mut().set_utf8_mouse(false);
+        write!(self.output, "\x1b[?1016l")?; // Disable SGR-pixels coordinate reporting
         write!(self.output, "\x1b[?1006l")?; // Disable SGR extended coordinate reporting
         write!(self.output, "\x1b[?1015l")?; // Disable urxvt extended coordinate reporting
+        write!(self.output, "\x1b[?1005l")?; // Disable UTF-8 extended coordinate reporting
+        write!(self.output, "\x1b[?1003l")?; // Disable all motion tracking
         write!(self.output, "\x1b[?1002l")?; // Disable button event tracking
         write!(self.output, "\x1b[?1000l")?; // Disable basic mouse reporting
         self.output.flush()?;
         Ok(())
     }
 
+    /// Enables or disables SGR-pixels mouse mode (mode 1016).
+    ///
+    /// In this mode, mouse events report their position in pixels as
+    /// [`MouseInput::pixel_position`](crate::MouseInput::pixel_position), and
+    /// [`MouseInput::position`](crate::MouseInput::position) is derived from it using
+    /// [`Terminal::cell_pixel_size()`] (or is zero if the cell size is unknown).
+    ///
+    /// This requires mouse input to be enabled (see [`Terminal::enable_mouse_input()`]).
+    /// Note that a terminal not supporting this mode keeps reporting cell coordinates,
+    /// which cannot be told apart from pixel coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tuinix::Terminal;
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// terminal.enable_mouse_input()?;
+    /// terminal.set_pixel_mouse_input(true)?;
+    /// if terminal.cell_pixel_size().is_empty() {
+    ///     terminal.query_cell_pixel_size(Duration::from_millis(100))?;
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn set_pixel_mouse_input(&mut self, enabled: bool) -> std::io::Result<()> {
+        if enabled {
+            write!(self.output, "\x1b[?1016h")?;
+        } else {
+            write!(self.output, "\x1b[?1016l")?;
+        }
+        self.output.flush()?;
+        self.input.parser_mut().set_pixel_mouse(enabled);
+        Ok(())
+    }
+
+    /// Enables or disables UTF-8 mouse mode (mode 1005).
+    ///
+    /// Terminals supporting neither SGR (mode 1006) nor urxvt (mode 1015) reports send
+    /// mouse positions as single bytes, which cannot represent columns or rows beyond 223.
+    /// In this mode, such terminals encode them as UTF-8 characters instead.
+    ///
+    /// This is disabled by default because terminals not supporting this mode keep sending
+    /// single bytes, and some positions beyond 160 would then be misdecoded as UTF-8 characters.
+    /// Enable it only for terminals known to support it.
+    /// This requires mouse input to be enabled (see [`Terminal::enable_mouse_input()`]).
+    pub fn set_utf8_mouse_input(&mut self, enabled: bool) -> std::io::Result<()> {
+        if enabled {
+            write!(self.output, "\x1b[?1005h")?;
+        } else {
+            write!(self.output, "\x1b[?1005l")?;
+        }
+        self.output.flush()?;
+        self.input.parser_mut().set_utf8_mouse(enabled);
+        Ok(())
+    }
+
+    /// Enables or disables the application cursor keys mode (DECCKM).
+    ///
+    /// In this mode, most terminals send the arrow, Home and End keys as SS3 sequences
+    /// (e.g., `ESC O A`) instead of CSI sequences (e.g., `ESC [ A`).
+    /// Both are decoded into the same [`KeyCode`](crate::KeyCode)s, so this only matters
+    /// to applications that expect the terminal to be in a specific mode.
+    /// The normal mode is restored when the [`Terminal`] is dropped or the program panics.
+    pub fn set_application_cursor(&mut self, enabled: bool) -> std::io::Result<()> {
+        if enabled {
+            RESTORE_FLAGS.fetch_or(RESTORE_APPLICATION_CURSOR, Ordering::SeqCst);
+            write!(self.output, "\x1b[?1h")?;
+        } else {
+            write!(self.output, "\x1b[?1l")?;
+        }
+        self.output.flush()
+    }
+
+    /// Enables or disables the application keypad mode (DECKPAM / DECKPNM).
+    ///
+    /// In this mode, terminals send the numeric keypad keys as SS3 sequences
+    /// (e.g., `ESC O M` for Enter and `ESC O k` for `+`), so they can be told apart
+    /// from the main keys. They are reported as the corresponding main keys unless
+    /// [`Terminal::set_distinct_keypad_keys()`] is enabled.
+    /// The numeric keypad mode is restored when the [`Terminal`] is dropped or the program panics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tuinix::Terminal;
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// terminal.set_application_keypad(true)?;
+    /// terminal.set_distinct_keypad_keys(true);
+    /// // Now the keypad `+` is reported as `KeyCode::Keypad('+')`
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn set_application_keypad(&mut self, enabled: bool) -> std::io::Result<()> {
+        if enabled {
+            RESTORE_FLAGS.fetch_or(RESTORE_APPLICATION_KEYPAD, Ordering::SeqCst);
+            write!(self.output, "\x1b=")?;
+        } else {
+            write!(self.output, "\x1b>")?;
+        }
+        self.output.flush()
+    }
+
+    /// Sets whether keypad keys sent in the application keypad mode are reported as
+    /// [`KeyCode::Keypad`](crate::KeyCode::Keypad) and
+    /// [`KeyCode::KeypadEnter`](crate::KeyCode::KeypadEnter) (disabled by default).
+    pub fn set_distinct_keypad_keys(&mut self, enabled: bool) {
+        self.input.parser_mut().set_distinct_keypad_keys(enabled);
+    }
+
+    /// Sets whether LF (0x0a) is reported as Enter rather than Ctrl+J (disabled by default).
+    ///
+    /// See [`InputParser::set_linefeed_as_enter()`](crate::InputParser::set_linefeed_as_enter) for details.
+    pub fn set_linefeed_as_enter(&mut self, enabled: bool) {
+        self.input.parser_mut().set_linefeed_as_enter(enabled);
+    }
+
+    /// Sets the dialect of the key sequences sent by the terminal.
+    ///
+    /// By default, the dialect is guessed from the terminfo entry or the name of the
+    /// terminal given by the `TERM` environment variable (see [`InputDialect::from_env()`]).
+    pub fn set_input_dialect(&mut self, dialect: InputDialect) {
+        self.input.parser_mut().set_dialect(dialect);
+    }
+
+    /// Enables or disables xterm's `modifyOtherKeys` (level 2).
+    ///
+    /// Without it, terminals cannot report many key combinations, such as Ctrl+1,
+    /// Ctrl+Enter or Ctrl+Shift+A (which is sent the same as Ctrl+A).
+    /// When enabled, supporting terminals (e.g., xterm, and others emulating it) report them as
+    /// `CSI 27 ; <modifier> ; <code> ~`, which is decoded into the corresponding [`KeyInput`](crate::KeyInput).
+    /// As [`KeyInput`](crate::KeyInput) has no Shift flag, Shift is only reflected in
+    /// the case of letters and as [`KeyCode::BackTab`](crate::KeyCode::BackTab).
+    ///
+    /// The terminal's default is restored when the [`Terminal`] is dropped or the program panics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tuinix::Terminal;
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// terminal.set_modify_other_keys(true)?;
+    /// // Now Ctrl+Enter is reported as `KeyInput { ctrl: true, alt: false, code: KeyCode::Enter }`
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn set_modify_other_keys(&mut self, enabled: bool) -> std::io::Result<()> {
+        if enabled {
+            RESTORE_FLAGS.fetch_or(RESTORE_MODIFY_OTHER_KEYS, Ordering::SeqCst);
+            write!(self.output, "\x1b[>4;2m")?;
+        } else {
+            write!(self.output, "\x1b[>4m")?;
+        }
+        self.output.flush()
+    }
+
+    /// Temporarily restores the original (cooked) terminal mode while running `f`,
+    /// then switches back to raw mode.
+    ///
+    /// This is useful for line-oriented interaction through the terminal, such as a password
+    /// prompt. Only the termios settings are restored, so the alternate screen, cursor
+    /// visibility and mouse reporting stay as they are. Pending output (including a deferred
+    /// frame) is flushed before running `f`, blocking if needed.
+    ///
+    /// If non-blocking output is enabled (see [`Terminal::set_nonblocking_output()`]),
+    /// it is disabled while `f` runs, so that `f` can read and write the terminal as usual,
+    /// and enabled again afterwards.
+    ///
+    /// The termios settings are switched after the written output has been transmitted,
+    /// without discarding unread input, so keys typed ahead are not lost.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io::BufRead;
+    /// use tuinix::Terminal;
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// let line = terminal.with_cooked_mode(|| {
+    ///     let mut line = String::new();
+    ///     std::io::stdin().lock().read_line(&mut line).map(|_| line)
+    /// })??;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn with_cooked_mode<F, T>(&mut self, f: F) -> std::io::Result<T>
+    where
+        F: FnOnce() -> T,
+    {
+        let nonblocking = self.output.is_nonblocking();
+        self.set_nonblocking_output(false)?;
+        self.disable_raw_mode()?;
+        let value = f();
+        self.enable_raw_mode()?;
+        self.set_nonblocking_output(nonblocking)?;
+        Ok(value)
+    }
+
     /// Waits for and returns the next terminal event.
     ///
     /// This method efficiently waits for either input events, terminal resize events,
     /// or custom file descriptor events using [`libc::select()`].
     ///
+    /// While there is pending output in non-blocking output mode
+    /// (see [`Terminal::set_nonblocking_output()`]), this method also flushes it
+    /// incrementally whenever the terminal becomes writable.
+    ///
     /// If you want to use I/O polling mechanisms other than [`libc::select()`],
     /// please use the following methods directly:
     /// - [`Terminal::input_fd()`] and [`Terminal::read_input()`] for input events
     /// - [`Terminal::signal_fd()`] and [`Terminal::wait_for_resize()`] for resize events
+    /// - [`Terminal::output_fd()`] and [`Terminal::flush_output()`] for pending output
     ///
     /// # Parameters
     ///
@@ -320,8 +875,38 @@ impl Terminal {
         additional_writefds: &[RawFd],
         timeout: Option<Duration>,
     ) -> std::io::Result<Option<TerminalEvent>> {
-        if let Some(input) = self.input.read_input_from_buf()? {
-            return Ok(Some(TerminalEvent::Input(input)));
+        self.poll_timed_event(additional_readfds, additional_writefds, timeout)
+            .map(|event| event.map(|event| event.event))
+    }
+
+    /// Like [`Terminal::poll_event()`], but also returns the time at which the event occurred.
+    ///
+    /// For input events, this is the time at which the bytes of the input were read from
+    /// the terminal, so it is not delayed by the time spent before the input is taken out.
+    /// For other events, this is the time at which the event was detected.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tuinix::{Terminal, TerminalEvent};
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// while let Some(event) = terminal.poll_timed_event(&[], &[], None)? {
+    ///     if let TerminalEvent::Input(input) = event.event {
+    ///         println!("{input} ({:?} ago)", event.time.elapsed());
+    ///     }
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn poll_timed_event(
+        &mut self,
+        additional_readfds: &[RawFd],
+        additional_writefds: &[RawFd],
+        timeout: Option<Duration>,
+    ) -> std::io::Result<Option<TimedEvent>> {
+        if let Some((input, time)) = self.input.parser_mut().next_timed_input() {
+            let event = TerminalEvent::Input(input);
+            return Ok(Some(TimedEvent { event, time }));
         }
 
         let start_time = Instant::now();
@@ -341,14 +926,19 @@ impl Terminal {
                 }
                 let mut readfds = readfds.assume_init();
 
-                // Add extra write fds
+                // Monitor the output fd while there is pending output
+                let watch_output = self.has_pending_output();
                 let mut writefds = MaybeUninit::<libc::fd_set>::zeroed();
-                if !additional_writefds.is_empty() {
-                    libc::FD_ZERO(writefds.as_mut_ptr());
-                    for &fd in additional_writefds {
-                        libc::FD_SET(fd, writefds.as_mut_ptr());
-                        maxfd = maxfd.max(fd);
-                    }
+                libc::FD_ZERO(writefds.as_mut_ptr());
+                if watch_output {
+                    libc::FD_SET(self.output_fd(), writefds.as_mut_ptr());
+                    maxfd = maxfd.max(self.output_fd());
+                }
+
+                // Add extra write fds
+                for &fd in additional_writefds {
+                    libc::FD_SET(fd, writefds.as_mut_ptr());
+                    maxfd = maxfd.max(fd);
                 }
                 let mut writefds = writefds.assume_init();
 
@@ -366,7 +956,7 @@ impl Terminal {
                 let ret = libc::select(
                     maxfd + 1,
                     &mut readfds,
-                    if additional_writefds.is_empty() {
+                    if additional_writefds.is_empty() && !watch_output {
                         std::ptr::null_mut()
                     } else {
                         &mut writefds
@@ -387,20 +977,28 @@ impl Terminal {
                 }
 
                 // Check built-in fds first
-                if libc::FD_ISSET(self.input_fd(), &readfds)
-                    && let Some(input) = self.read_input()?
-                {
-                    return Ok(Some(TerminalEvent::Input(input)));
+                if watch_output && libc::FD_ISSET(self.output_fd(), &writefds) {
+                    self.flush_output()?;
+                }
+                if libc::FD_ISSET(self.input_fd(), &readfds) {
+                    self.input.fill_ready_buf()?;
+                    if let Some((input, time)) = self.input.parser_mut().next_timed_input() {
+                        let event = TerminalEvent::Input(input);
+                        return Ok(Some(TimedEvent { event, time }));
+                    }
                 }
+                let time = Instant::now();
                 if libc::FD_ISSET(self.signal_fd(), &readfds) {
-                    return self.wait_for_resize().map(TerminalEvent::Resize).map(Some);
+                    let event = TerminalEvent::Resize(self.wait_for_resize()?);
+                    return Ok(Some(TimedEvent { event, time }));
                 }
 
                 // Check extra read fds
                 for &fd in additional_readfds {
                     if libc::FD_ISSET(fd, &readfds) {
                         let readable = true;
-                        return Ok(Some(TerminalEvent::FdReady { fd, readable }));
+                        let event = TerminalEvent::FdReady { fd, readable };
+                        return Ok(Some(TimedEvent { event, time }));
                     }
                 }
 
@@ -408,43 +1006,130 @@ impl Terminal {
                 for &fd in additional_writefds {
                     if libc::FD_ISSET(fd, &writefds) {
                         let readable = false;
-                        return Ok(Some(TerminalEvent::FdReady { fd, readable }));
+                        let event = TerminalEvent::FdReady { fd, readable };
+                        return Ok(Some(TimedEvent { event, time }));
                     }
                 }
             }
         }
     }
 
-    /// Reads and processes the next input event from the terminal.
-    ///
-    /// This method attempts to read raw bytes from stdin and parse them into a
-    /// structured [`TerminalInput`] event.
-    ///
-    /// By default, this method blocks until input is available. To use it in non-blocking
-    /// mode, first call [`set_nonblocking()`](crate::set_nonblocking) on [`Terminal::input_fd()`].
-    ///
-    /// While [`Terminal::poll_event()`] is generally recommended for receiving terminal input events,
-    /// you may need to call this method directly when using external I/O polling crates like `mio`.
-    ///
-    /// # Returns
+    /// Returns all terminal events that are already buffered or can be read without blocking.
     ///
-    /// - `Ok(Some(input))` if an input event was successfully read and parsed
-    /// - `Ok(None)` if not enough bytes were available to form a complete input event
-    /// - `Err(e)` if an I/O error occurred while reading from stdin
+    /// Unlike [`Terminal::poll_event()`], which returns one event per call, this method lets
+    /// an application handle a burst of inputs (e.g., fast mouse motion or wheel scrolling)
+    /// and redraw only once. Pending resize notifications are merged into a single
+    /// [`TerminalEvent::Resize`] placed after the inputs.
     ///
-    /// # Errors
+    /// Returns an empty vector if no event is available.
     ///
-    /// This method returns an error if reading from stdin fails or encounters EOF.
-    pub fn read_input(&mut self) -> std::io::Result<Option<TerminalInput>> {
-        self.input.read_input()
-    }
-
-    /// Waits for a terminal resize event to occur and returns the new terminal size.
+    /// # Examples
     ///
-    /// By default, this method blocks until input is available. To use it in non-blocking
-    /// mode, first call [`set_nonblocking()`](crate::set_nonblocking) on [`Terminal::signal_fd()`].
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tuinix::Terminal;
     ///
-    /// While [`Terminal::poll_event()`] is generally recommended for detecting terminal resize events,
+    /// let mut terminal = Terminal::new()?;
+    /// while let Some(event) = terminal.poll_event(&[], &[], Some(Duration::from_secs(10)))? {
+    ///     // Handle the whole burst of events, then redraw once
+    ///     let mut events = vec![event];
+    ///     events.extend(terminal.drain_events()?);
+    ///     println!("{} events", events.len());
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn drain_events(&mut self) -> std::io::Result<Vec<TerminalEvent>> {
+        let mut events = Vec::new();
+        loop {
+            while let Some(input) = self.input.parser_mut().next_input() {
+                events.push(TerminalEvent::Input(input));
+            }
+            if !wait_readable(self.input_fd(), Duration::ZERO)? {
+                break;
+            }
+            match self.input.fill_ready_buf() {
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                result => result?,
+            }
+        }
+
+        let mut resized = false;
+        while wait_readable(self.signal_fd(), Duration::ZERO)? {
+            match self.signal.read(&mut [0]) {
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+                Ok(_) => resized = true,
+            }
+        }
+        if resized {
+            self.update_size()?;
+            events.push(TerminalEvent::Resize(self.size));
+        }
+
+        Ok(events)
+    }
+
+    /// Like [`Terminal::drain_events()`], but merges redundant events so a burst costs one redraw.
+    ///
+    /// The following runs of consecutive events are merged:
+    ///
+    /// - [`MouseEvent::Drag`](crate::MouseEvent::Drag) and [`MouseEvent::Move`](crate::MouseEvent::Move)
+    ///   inputs collapse to the latest position
+    /// - Scroll inputs in the same direction are merged, and [`CoalescedEvent::count`] tells how many
+    /// - Resize events collapse to the final size
+    ///
+    /// Inputs are merged only if they have the same event kind and modifiers.
+    /// All other events are returned as-is with a count of `1`.
+    pub fn drain_coalesced_events(&mut self) -> std::io::Result<Vec<CoalescedEvent>> {
+        self.drain_events().map(coalesce_events)
+    }
+
+    /// Reads and processes the next input event from the terminal.
+    ///
+    /// This method attempts to read raw bytes from stdin and parse them into a
+    /// structured [`TerminalInput`] event.
+    ///
+    /// By default, this method blocks until input is available. To use it in non-blocking
+    /// mode, first call [`set_nonblocking()`](crate::set_nonblocking) on [`Terminal::input_fd()`].
+    ///
+    /// Note that [`Terminal::set_nonblocking_output()`] also makes this method non-blocking
+    /// if the input and output fds share the same open file description, which is usually
+    /// the case for a tty.
+    ///
+    /// While [`Terminal::poll_event()`] is generally recommended for receiving terminal input events,
+    /// you may need to call this method directly when using external I/O polling crates like `mio`.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(input))` if an input event was successfully read and parsed
+    /// - `Ok(None)` if not enough bytes were available to form a complete input event
+    /// - `Err(e)` if an I/O error occurred while reading from stdin
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if reading from stdin fails or encounters EOF.
+    /// In non-blocking mode, it returns an error of kind [`ErrorKind::WouldBlock`]
+    /// if no bytes are available (see [`try_nonblocking()`](crate::try_nonblocking)).
+    pub fn read_input(&mut self) -> std::io::Result<Option<TerminalInput>> {
+        self.input.read_input()
+    }
+
+    /// Sets the maximum length in bytes of a single input sequence (1 MiB by default).
+    ///
+    /// The input buffer grows on demand up to this limit to hold incomplete sequences,
+    /// such as long OSC 52 clipboard replies. A sequence exceeding the limit is discarded
+    /// along with its remaining bytes, and decoding resumes with the next input.
+    pub fn set_max_input_sequence_len(&mut self, len: usize) {
+        self.input.parser_mut().set_max_sequence_len(len);
+    }
+
+    /// Waits for a terminal resize event to occur and returns the new terminal size.
+    ///
+    /// By default, this method blocks until input is available. To use it in non-blocking
+    /// mode, first call [`set_nonblocking()`](crate::set_nonblocking) on [`Terminal::signal_fd()`].
+    ///
+    /// While [`Terminal::poll_event()`] is generally recommended for detecting terminal resize events,
     /// you may need to call this method directly when using external I/O polling crates like `mio`.
     pub fn wait_for_resize(&mut self) -> std::io::Result<TerminalSize> {
         self.signal.read_exact(&mut [0])?;
@@ -476,7 +1161,235 @@ impl Terminal {
     /// # Ok::<(), std::io::Error>(())
     /// ```
     pub fn set_cursor(&mut self, position: Option<TerminalPosition>) {
-        self.cursor = position;
+        self.renderer.cursor = position;
+    }
+
+    /// Queries the current cursor position on the terminal (DECXCPR) and waits for the reply.
+    ///
+    /// This reflects the actual position on the screen, which may differ from the one given to
+    /// [`Terminal::set_cursor()`] (e.g., after writing to the terminal outside of frames).
+    /// Returns `Ok(None)` if the terminal does not reply within `timeout`.
+    ///
+    /// Inputs received while waiting for the reply are kept and returned by subsequent
+    /// calls to [`Terminal::poll_event()`] or [`Terminal::read_input()`].
+    ///
+    /// The extended form of the query (`CSI ? 6 n`) is used, as the reply to the plain one
+    /// has the same form as a modified F3 key (e.g., `ESC [ 1 ; 2 R`).
+    /// Terminals that reply to it in the plain form are supported as well, except when the
+    /// cursor is in the first row and in one of the columns 2 to 16: such a reply is
+    /// indistinguishable from a modified F3 key, so it is reported as a key input instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tuinix::Terminal;
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// if let Some(position) = terminal.cursor_position(Duration::from_millis(100))? {
+    ///     println!("cursor at row {}, col {}", position.row, position.col);
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn cursor_position(
+        &mut self,
+        timeout: Duration,
+    ) -> std::io::Result<Option<TerminalPosition>> {
+        write!(self.output, "\x1b[?6n")?;
+        self.output.flush()?;
+
+        self.wait_for_response(timeout, |response| match response {
+            TerminalResponse::CursorPosition(position) => Some(*position),
+            _ => None,
+        })
+    }
+
+    /// Sets the shape and blinking behavior of the cursor.
+    ///
+    /// Unlike [`Terminal::set_cursor()`], this takes effect immediately.
+    /// The terminal's default cursor style is restored when the [`Terminal`] is dropped
+    /// or the program panics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tuinix::{CursorStyle, Terminal};
+    ///
+    /// let mut terminal = Terminal::new()?;
+    ///
+    /// // Block cursor in normal mode, bar cursor in insert mode
+    /// terminal.set_cursor_style(CursorStyle::SteadyBlock)?;
+    /// terminal.set_cursor_style(CursorStyle::BlinkingBar)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn set_cursor_style(&mut self, style: CursorStyle) -> std::io::Result<()> {
+        RESTORE_FLAGS.fetch_or(RESTORE_CURSOR_STYLE, Ordering::SeqCst);
+        write!(self.output, "{style}")?;
+        self.output.flush()
+    }
+
+    /// Sets the color of the cursor (OSC 12).
+    ///
+    /// Passing `None` resets the cursor color to the terminal's default (OSC 112),
+    /// which also happens automatically when the [`Terminal`] is dropped or the program panics.
+    pub fn set_cursor_color(&mut self, color: Option<TerminalColor>) -> std::io::Result<()> {
+        write_cursor_color(&mut self.output, color)?;
+        self.output.flush()
+    }
+
+    /// Queries the terminal's default foreground (text) color (OSC 10).
+    ///
+    /// Returns `Ok(None)` if the terminal does not reply within `timeout`.
+    ///
+    /// Inputs received while waiting for the reply are kept and returned by subsequent
+    /// calls to [`Terminal::poll_event()`] or [`Terminal::read_input()`].
+    pub fn query_foreground_color(
+        &mut self,
+        timeout: Duration,
+    ) -> std::io::Result<Option<TerminalColor>> {
+        self.query_color("10", timeout)
+    }
+
+    /// Queries the terminal's default background color (OSC 11).
+    ///
+    /// This is useful for choosing a light or dark theme automatically.
+    /// Returns `Ok(None)` if the terminal does not reply within `timeout`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use tuinix::Terminal;
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// if let Some(bg) = terminal.query_background_color(Duration::from_millis(100))? {
+    ///     let luminance = 0.299 * bg.r as f32 + 0.587 * bg.g as f32 + 0.114 * bg.b as f32;
+    ///     let dark = luminance < 128.0;
+    ///     println!("dark theme: {dark}");
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn query_background_color(
+        &mut self,
+        timeout: Duration,
+    ) -> std::io::Result<Option<TerminalColor>> {
+        self.query_color("11", timeout)
+    }
+
+    /// Queries the color of an entry in the terminal's 256-color palette (OSC 4).
+    ///
+    /// Returns `Ok(None)` if the terminal does not reply within `timeout`.
+    pub fn query_palette_color(
+        &mut self,
+        index: u8,
+        timeout: Duration,
+    ) -> std::io::Result<Option<TerminalColor>> {
+        self.query_color(&format!("4;{index}"), timeout)
+    }
+
+    /// Sets the terminal's default foreground (text) color (OSC 10).
+    ///
+    /// The original color is restored (OSC 110) when the [`Terminal`] is dropped
+    /// or the program panics.
+    pub fn set_foreground_color(&mut self, color: TerminalColor) -> std::io::Result<()> {
+        RESTORE_FLAGS.fetch_or(RESTORE_FOREGROUND_COLOR, Ordering::SeqCst);
+        write!(self.output, "\x1b]10;{}\x1b\\", color.to_x11_rgb())?;
+        self.output.flush()
+    }
+
+    /// Sets the terminal's default background color (OSC 11).
+    ///
+    /// The original color is restored (OSC 111) when the [`Terminal`] is dropped
+    /// or the program panics.
+    pub fn set_background_color(&mut self, color: TerminalColor) -> std::io::Result<()> {
+        RESTORE_FLAGS.fetch_or(RESTORE_BACKGROUND_COLOR, Ordering::SeqCst);
+        write!(self.output, "\x1b]11;{}\x1b\\", color.to_x11_rgb())?;
+        self.output.flush()
+    }
+
+    /// Sets the color of an entry in the terminal's 256-color palette (OSC 4).
+    ///
+    /// The original palette is restored (OSC 104) when the [`Terminal`] is dropped
+    /// or the program panics.
+    pub fn set_palette_color(&mut self, index: u8, color: TerminalColor) -> std::io::Result<()> {
+        RESTORE_FLAGS.fetch_or(RESTORE_PALETTE, Ordering::SeqCst);
+        write!(self.output, "\x1b]4;{index};{}\x1b\\", color.to_x11_rgb())?;
+        self.output.flush()
+    }
+
+    fn query_color(
+        &mut self,
+        target: &str,
+        timeout: Duration,
+    ) -> std::io::Result<Option<TerminalColor>> {
+        write!(self.output, "\x1b]{target};?\x1b\\")?;
+        self.output.flush()?;
+
+        self.wait_for_response(timeout, |response| {
+            let TerminalResponse::Osc(body) = response else {
+                return None;
+            };
+            let spec = body.strip_prefix(target)?.strip_prefix(';')?;
+            TerminalColor::from_x11_rgb(spec)
+        })
+    }
+
+    /// Sets the shape of the mouse pointer while it is over the terminal (OSC 22).
+    ///
+    /// `shape` is a pointer name understood by the terminal, such as `"text"`, `"pointer"` or
+    /// `"crosshair"` (the supported names depend on the terminal).
+    /// Passing `None` resets the pointer to the terminal's default,
+    /// which also happens automatically when the [`Terminal`] is dropped or the program panics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidInput`] error if `shape` contains control characters.
+    pub fn set_pointer_shape(&mut self, shape: Option<&str>) -> std::io::Result<()> {
+        write_pointer_shape(&mut self.output, shape)?;
+        self.output.flush()
+    }
+
+    /// Returns statistics about the most recent frame rendered by [`Terminal::draw()`],
+    /// [`Terminal::draw_ref()`] or [`Terminal::draw_and_swap()`].
+    ///
+    /// Frames deferred in non-blocking output mode are counted when they are actually rendered.
+    pub fn last_render_stats(&self) -> RenderStats {
+        self.renderer.last_render_stats
+    }
+
+    /// Returns statistics accumulated over all frames rendered since the terminal was created
+    /// or since the last call to [`Terminal::reset_render_stats()`].
+    ///
+    /// [`RenderStats::frames`] holds the number of rendered frames, so averages can be derived
+    /// from the totals. Resetting the statistics periodically (e.g., every second) gives
+    /// rolling aggregates suitable for a debug overlay.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fmt::Write;
+    /// use tuinix::{Terminal, TerminalFrame};
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// let mut frame: TerminalFrame = TerminalFrame::new(terminal.size());
+    /// for i in 0..100 {
+    ///     writeln!(frame, "Tick: {i}")?;
+    ///     terminal.draw_and_swap(&mut frame)?;
+    /// }
+    ///
+    /// let stats = terminal.total_render_stats();
+    /// let average = stats.elapsed / stats.frames.max(1) as u32;
+    /// println!("changed {} cells in {:?} per frame on average", stats.cells_changed, average);
+    /// terminal.reset_render_stats();
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn total_render_stats(&self) -> RenderStats {
+        self.renderer.total_render_stats
+    }
+
+    /// Resets the statistics returned by [`Terminal::total_render_stats()`].
+    pub fn reset_render_stats(&mut self) {
+        self.renderer.total_render_stats = RenderStats::default();
     }
 
     /// Draws a frame to the terminal screen.
@@ -487,6 +1400,9 @@ impl Terminal {
     /// The frame is saved internally, allowing subsequent calls to only update
     /// changed portions of the screen for better performance.
     ///
+    /// To avoid allocating a new frame on every update, see
+    /// [`Terminal::draw_ref()`] and [`Terminal::draw_and_swap()`].
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -507,45 +1423,45 @@ impl Terminal {
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn draw<W>(&mut self, frame: TerminalFrame<W>) -> std::io::Result<()> {
-        let frame = frame.finish();
-        self.hide_cursor()?;
-
-        let move_cursor = |output: &mut BufWriter<_>, position: TerminalPosition| {
-            write!(output, "\x1b[{};{}H", position.row + 1, position.col + 1)
-        };
-
-        let resized = self.last_frame.size() != frame.size();
-        let mut skipped = false;
-        let mut last_style = None;
-        let mut last_row = usize::MAX;
-        for (position, c) in frame.chars() {
-            let old = self.last_frame.get_char(position);
-            if !resized && Some(c) == old {
-                skipped = true;
-                continue;
-            }
-
-            if skipped || last_row != position.row {
-                move_cursor(&mut self.output, position)?;
-            }
-            if Some(c.style) != last_style {
-                write!(self.output, "{}", c.style)?;
-            }
-            write!(self.output, "{}", c.value)?;
-
-            last_style = Some(c.style);
-            last_row = position.row;
-            skipped = false;
-        }
-
-        if let Some(position) = self.cursor {
-            move_cursor(&mut self.output, position)?;
-            self.show_cursor()?;
-        }
+        self.renderer.draw(&mut self.output, frame)
+    }
 
-        self.output.flush()?;
-        self.last_frame = frame;
+    /// Draws a frame to the terminal screen without taking ownership of it.
+    ///
+    /// This behaves like [`Terminal::draw()`], but copies the frame contents into
+    /// the internally saved frame instead of moving the frame.
+    /// Once the terminal size is stable, this copy reuses the existing buffer,
+    /// so the caller can keep and redraw the same frame without allocating.
+    pub fn draw_ref<W>(&mut self, frame: &TerminalFrame<W>) -> std::io::Result<()> {
+        self.renderer.draw_ref(&mut self.output, frame)
+    }
 
+    /// Draws a frame to the terminal screen and hands back a cleared frame for the next update.
+    ///
+    /// After rendering, the buffer of `frame` is swapped with the internally saved frame.
+    /// `frame` is then cleared and resized to the current terminal size, ready to be
+    /// written again.
+    /// Rendering with the same pair of buffers back and forth (double buffering) does
+    /// not allocate once the terminal size is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fmt::Write;
+    /// use tuinix::{Terminal, TerminalFrame};
+    ///
+    /// let mut terminal = Terminal::new()?;
+    /// let mut frame: TerminalFrame = TerminalFrame::new(terminal.size());
+    ///
+    /// for i in 0..10 {
+    ///     writeln!(frame, "Tick: {i}")?;
+    ///     terminal.draw_and_swap(&mut frame)?;
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn draw_and_swap<W>(&mut self, frame: &mut TerminalFrame<W>) -> std::io::Result<()> {
+        self.renderer.draw_and_swap(&mut self.output, frame)?;
+        frame.resize(self.size);
         Ok(())
     }
 
@@ -567,6 +1483,18 @@ impl Terminal {
         self.size.rows = winsize.ws_row as usize;
         self.size.cols = winsize.ws_col as usize;
 
+        // Many terminals leave the pixel size zero, in which case
+        // the value obtained by `query_cell_pixel_size()` (if any) is kept
+        if winsize.ws_xpixel > 0 && winsize.ws_ypixel > 0 && !self.size.is_empty() {
+            self.cell_pixel_size = PixelSize::width_height(
+                winsize.ws_xpixel as usize / self.size.cols,
+                winsize.ws_ypixel as usize / self.size.rows,
+            );
+            self.input
+                .parser_mut()
+                .set_cell_pixel_size(self.cell_pixel_size);
+        }
+
         Ok(())
     }
 
@@ -579,34 +1507,19 @@ impl Terminal {
     }
 
     fn enable_raw_mode(&mut self) -> std::io::Result<()> {
-        let mut raw = self.original_termios;
+        let config = self.raw_mode_config;
+        let raw = raw_termios(self.original_termios, config);
+        check_libc_result(unsafe { libc::tcsetattr(self.input_fd(), libc::TCSADRAIN, &raw) })?;
 
-        // Input modes: no break, no CR to NL, no parity check, no strip char,
-        // no start/stop output control.
-        raw.c_iflag &= !(libc::BRKINT | libc::ICRNL | libc::INPCK | libc::ISTRIP | libc::IXON);
-
-        // Output modes - disable post processing
-        raw.c_oflag &= !libc::OPOST;
-
-        // Control modes - clear size bits, parity checking off, set 8 bit chars
-        raw.c_cflag &= !(libc::CSIZE | libc::PARENB);
-        raw.c_cflag |= libc::CS8;
-
-        // Local modes - disable echoing, canonical mode, signal chars, and extended features
-        raw.c_lflag &= !(libc::ECHO | libc::ICANON | libc::IEXTEN | libc::ISIG);
-
-        // 1 byte at a time, no timer
-        raw.c_cc[libc::VMIN] = 1;
-        raw.c_cc[libc::VTIME] = 0;
-
-        check_libc_result(unsafe { libc::tcsetattr(self.input_fd(), libc::TCSAFLUSH, &raw) })?;
+        // With VMIN=0, a read that times out returns no bytes, which is not an EOF
+        self.input.set_empty_read_allowed(config.min_bytes == 0);
 
         Ok(())
     }
 
     fn disable_raw_mode(&mut self) -> std::io::Result<()> {
         check_libc_result(unsafe {
-            libc::tcsetattr(self.input_fd(), libc::TCSAFLUSH, &self.original_termios)
+            libc::tcsetattr(self.input_fd(), libc::TCSADRAIN, &self.original_termios)
         })?;
         Ok(())
     }
@@ -614,7 +1527,9 @@ impl Terminal {
 
 impl Drop for Terminal {
     fn drop(&mut self) {
+        let _ = self.output.set_nonblocking(false);
         let _ = self.disable_mouse_input();
+        let _ = write_restore_sequences(&mut self.output);
         let _ = self.disable_alternate_screen();
         let _ = self.disable_raw_mode();
         let _ = self.show_cursor();
@@ -624,6 +1539,196 @@ impl Drop for Terminal {
     }
 }
 
+/// Frame rendering state of a [`Terminal`].
+///
+/// This is kept apart from the terminal so that it works with any [`OutputWriter`].
+#[derive(Debug, Default)]
+struct Renderer {
+    last_frame: TerminalFrame,
+    pending_frame: TerminalFrame,
+    has_pending_frame: bool,
+    last_render_stats: RenderStats,
+    total_render_stats: RenderStats,
+    cursor: Option<TerminalPosition>,
+}
+
+impl Renderer {
+    fn has_pending_output<T: AsRawFd>(&self, output: &OutputWriter<T>) -> bool {
+        output.pending_bytes() > 0 || self.has_pending_frame
+    }
+
+    fn should_defer_frame<T: AsRawFd>(&self, output: &OutputWriter<T>) -> bool {
+        output.is_nonblocking() && self.has_pending_output(output)
+    }
+
+    fn draw<T: AsRawFd, W>(
+        &mut self,
+        output: &mut OutputWriter<T>,
+        frame: TerminalFrame<W>,
+    ) -> std::io::Result<()> {
+        if self.should_defer_frame(output) {
+            self.pending_frame = frame.finish();
+            self.has_pending_frame = true;
+            return Ok(());
+        }
+        self.render(output, &frame)?;
+        self.last_frame = frame.finish();
+        Ok(())
+    }
+
+    fn draw_ref<T: AsRawFd, W>(
+        &mut self,
+        output: &mut OutputWriter<T>,
+        frame: &TerminalFrame<W>,
+    ) -> std::io::Result<()> {
+        if self.should_defer_frame(output) {
+            self.pending_frame.copy_from(frame);
+            self.has_pending_frame = true;
+            return Ok(());
+        }
+        self.render(output, frame)?;
+        self.last_frame.copy_from(frame);
+        Ok(())
+    }
+
+    fn draw_and_swap<T: AsRawFd, W>(
+        &mut self,
+        output: &mut OutputWriter<T>,
+        frame: &mut TerminalFrame<W>,
+    ) -> std::io::Result<()> {
+        if self.should_defer_frame(output) {
+            self.pending_frame.swap_chars(frame);
+            self.has_pending_frame = true;
+        } else {
+            self.render(output, frame)?;
+            self.last_frame.swap_chars(frame);
+        }
+        Ok(())
+    }
+
+    /// Flushes pending output, and renders the deferred frame once the output drains.
+    fn flush<T: AsRawFd>(&mut self, output: &mut OutputWriter<T>) -> std::io::Result<bool> {
+        output.flush()?;
+        if self.has_pending_frame && output.pending_bytes() == 0 {
+            self.has_pending_frame = false;
+            let frame = std::mem::take(&mut self.pending_frame);
+            self.render(output, &frame)?;
+            self.pending_frame = std::mem::replace(&mut self.last_frame, frame);
+        }
+        Ok(!self.has_pending_output(output))
+    }
+
+    fn render<T: AsRawFd, W>(
+        &mut self,
+        output: &mut OutputWriter<T>,
+        frame: &TerminalFrame<W>,
+    ) -> std::io::Result<()> {
+        let start_time = Instant::now();
+        let start_bytes = output.total_bytes();
+        let mut stats = RenderStats {
+            frames: 1,
+            ..RenderStats::default()
+        };
+        write!(output, "\x1b[?25l")?; // Hide cursor
+
+        let move_cursor = |output: &mut OutputWriter<T>, position: TerminalPosition| {
+            write!(output, "\x1b[{};{}H", position.row + 1, position.col + 1)
+        };
+
+        let resized = self.last_frame.size() != frame.size();
+        let mut skipped = false;
+        let mut last_style = None;
+        let mut last_link = None;
+        let mut last_row = usize::MAX;
+        for (position, c) in frame.chars() {
+            stats.cells_compared += 1;
+            let link = frame.link(c);
+            let old = self.last_frame.get_char(position);
+            if !resized
+                && let Some(old) = old
+                && old.without_link() == c.without_link()
+                && self.last_frame.link(old) == link
+            {
+                skipped = true;
+                continue;
+            }
+
+            if skipped || last_row != position.row {
+                move_cursor(output, position)?;
+                stats.cursor_moves += 1;
+            }
+            if Some(c.style) != last_style {
+                write!(output, "{}", c.style)?;
+                stats.sgr_sequences += 1;
+            }
+            if link != last_link {
+                // Open (or close) OSC 8 hyperlinks when crossing link boundaries
+                write!(output, "{}", link.unwrap_or(&TerminalHyperlink::END))?;
+                stats.hyperlink_sequences += 1;
+            }
+            write!(output, "{}", c.value)?;
+            stats.cells_changed += 1;
+
+            last_style = Some(c.style);
+            last_link = link;
+            last_row = position.row;
+            skipped = false;
+        }
+        if last_link.is_some() {
+            write!(output, "{}", TerminalHyperlink::END)?;
+            stats.hyperlink_sequences += 1;
+        }
+
+        if let Some(position) = self.cursor {
+            move_cursor(output, position)?;
+            write!(output, "\x1b[?25h")?; // Show cursor
+            stats.cursor_moves += 1;
+        }
+
+        stats.bytes_written = (output.total_bytes() - start_bytes) as usize;
+        let result = output.flush();
+        stats.elapsed = start_time.elapsed();
+        self.last_render_stats = stats;
+        self.total_render_stats += stats;
+        result
+    }
+}
+
+/// Derives the raw mode settings from the original terminal settings.
+fn raw_termios(original: libc::termios, config: RawModeConfig) -> libc::termios {
+    let mut raw = original;
+
+    // Input modes: no break, no CR to NL, no parity check, no strip char,
+    // no start/stop output control (unless flow control is requested).
+    raw.c_iflag &= !(libc::BRKINT | libc::ICRNL | libc::INPCK | libc::ISTRIP);
+    if !config.flow_control {
+        raw.c_iflag &= !libc::IXON;
+    }
+
+    // Output modes - disable post processing (unless requested)
+    if !config.output_processing {
+        raw.c_oflag &= !libc::OPOST;
+    }
+
+    // Control modes - clear size bits, parity checking off, set 8 bit chars
+    raw.c_cflag &= !(libc::CSIZE | libc::PARENB);
+    raw.c_cflag |= libc::CS8;
+
+    // Local modes - disable echoing, canonical mode, extended features,
+    // and signal chars (unless requested)
+    raw.c_lflag &= !(libc::ECHO | libc::ICANON | libc::IEXTEN);
+    if !config.signals {
+        raw.c_lflag &= !libc::ISIG;
+    }
+
+    // Read granularity and timer (in tenths of a second)
+    let vtime = config.read_timeout.as_millis().div_ceil(100).min(255);
+    raw.c_cc[libc::VMIN] = config.min_bytes;
+    raw.c_cc[libc::VTIME] = vtime as libc::cc_t;
+
+    raw
+}
+
 impl std::fmt::Debug for Terminal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Terminal").finish()
@@ -653,6 +1758,372 @@ pub enum TerminalEvent {
     },
 }
 
+/// Terminal event returned by [`Terminal::drain_coalesced_events()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CoalescedEvent {
+    /// The latest event of the merged run.
+    pub event: TerminalEvent,
+
+    /// Number of events merged into this one (always at least `1`).
+    ///
+    /// For scroll inputs, this is the number of wheel steps.
+    pub count: usize,
+}
+
+/// Terminal event returned by [`Terminal::poll_timed_event()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimedEvent {
+    /// The event.
+    pub event: TerminalEvent,
+
+    /// Monotonic time at which the event occurred.
+    pub time: Instant,
+}
+
+fn coalesce_events(events: Vec<TerminalEvent>) -> Vec<CoalescedEvent> {
+    let mut coalesced: Vec<CoalescedEvent> = Vec::with_capacity(events.len());
+    for event in events {
+        if let Some(last) = coalesced.last_mut()
+            && is_mergeable(&last.event, &event)
+        {
+            last.event = event;
+            last.count += 1;
+        } else {
+            coalesced.push(CoalescedEvent { event, count: 1 });
+        }
+    }
+    coalesced
+}
+
+fn is_mergeable(prev: &TerminalEvent, next: &TerminalEvent) -> bool {
+    match (prev, next) {
+        (TerminalEvent::Resize(_), TerminalEvent::Resize(_)) => true,
+        (
+            TerminalEvent::Input(TerminalInput::Mouse(a)),
+            TerminalEvent::Input(TerminalInput::Mouse(b)),
+        ) => {
+            a.event == b.event
+                && a.ctrl == b.ctrl
+                && a.alt == b.alt
+                && a.shift == b.shift
+                && matches!(
+                    a.event,
+                    MouseEvent::Drag(_)
+                        | MouseEvent::Move
+                        | MouseEvent::ScrollUp
+                        | MouseEvent::ScrollDown
+                        | MouseEvent::ScrollLeft
+                        | MouseEvent::ScrollRight
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Raw-mode settings passed to [`Terminal::with_raw_mode_config()`].
+///
+/// By default, all terminal processing that would interfere with a TUI application is disabled,
+/// and reads return as soon as a byte is available.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use tuinix::RawModeConfig;
+///
+/// let config = RawModeConfig::new()
+///     .signals()
+///     .min_bytes(0)
+///     .read_timeout(Duration::from_millis(500));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawModeConfig {
+    /// Whether Ctrl-C, Ctrl-Z and `Ctrl-\` raise signals (`ISIG`)
+    /// instead of being read as key inputs.
+    pub signals: bool,
+
+    /// Whether output post-processing (`OPOST`), such as translating `\n` to `\r\n`,
+    /// is kept enabled.
+    pub output_processing: bool,
+
+    /// Whether Ctrl-S and Ctrl-Q pause and resume output (`IXON`)
+    /// instead of being read as key inputs.
+    pub flow_control: bool,
+
+    /// Minimum number of bytes a read waits for (`VMIN`).
+    ///
+    /// If this is zero, a read returns after [`RawModeConfig::read_timeout`] even when
+    /// no bytes are available, and [`Terminal::read_input()`] returns `Ok(None)`.
+    /// [`Terminal::poll_event()`] still reports an EOF (e.g., a hangup) as an error,
+    /// since it only reads once the input is readable.
+    pub min_bytes: u8,
+
+    /// Time a read waits for bytes (`VTIME`), rounded up to tenths of a second
+    /// and capped at 25.5 seconds.
+    ///
+    /// With a non-zero [`RawModeConfig::min_bytes`], this is the timeout between bytes.
+    pub read_timeout: Duration,
+}
+
+impl RawModeConfig {
+    /// Makes a new [`RawModeConfig`] instance with the default settings.
+    pub const fn new() -> Self {
+        Self {
+            signals: false,
+            output_processing: false,
+            flow_control: false,
+            min_bytes: 1,
+            read_timeout: Duration::ZERO,
+        }
+    }
+
+    /// Keeps signal generation by control characters enabled.
+    pub const fn signals(mut self) -> Self {
+        self.signals = true;
+        self
+    }
+
+    /// Keeps output post-processing enabled.
+    pub const fn output_processing(mut self) -> Self {
+        self.output_processing = true;
+        self
+    }
+
+    /// Keeps software flow control enabled.
+    pub const fn flow_control(mut self) -> Self {
+        self.flow_control = true;
+        self
+    }
+
+    /// Sets the minimum number of bytes a read waits for.
+    pub const fn min_bytes(mut self, n: u8) -> Self {
+        self.min_bytes = n;
+        self
+    }
+
+    /// Sets the time a read waits for bytes.
+    pub const fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+}
+
+impl Default for RawModeConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Mouse tracking level passed to [`Terminal::enable_mouse_tracking()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MouseTracking {
+    /// Reports button presses, releases and scrolling only (mode 1000).
+    Clicks,
+
+    /// Also reports motion while a button is held down (mode 1002),
+    /// as [`MouseEvent::Drag`](crate::MouseEvent::Drag).
+    #[default]
+    ButtonMotion,
+
+    /// Also reports motion with no button held down (mode 1003),
+    /// as [`MouseEvent::Move`](crate::MouseEvent::Move).
+    AnyMotion,
+}
+
+/// Clipboard selection targeted by [`Terminal::copy_to_clipboard()`] and
+/// [`Terminal::read_clipboard()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClipboardSelection {
+    /// The system clipboard.
+    #[default]
+    Clipboard,
+
+    /// The primary selection (X11), usually pasted with the middle mouse button.
+    Primary,
+
+    /// The secondary selection (X11).
+    Secondary,
+}
+
+impl ClipboardSelection {
+    fn as_char(self) -> char {
+        match self {
+            Self::Clipboard => 'c',
+            Self::Primary => 'p',
+            Self::Secondary => 'q',
+        }
+    }
+}
+
+/// Statistics about rendering frames, returned by [`Terminal::last_render_stats()`] and
+/// [`Terminal::total_render_stats()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RenderStats {
+    /// Number of rendered frames.
+    pub frames: usize,
+
+    /// Number of cells compared against the previous frame.
+    pub cells_compared: usize,
+
+    /// Number of cells that differed from the previous frame and were redrawn.
+    pub cells_changed: usize,
+
+    /// Number of cursor movement sequences emitted.
+    pub cursor_moves: usize,
+
+    /// Number of SGR (style) sequences emitted.
+    pub sgr_sequences: usize,
+
+    /// Number of OSC 8 sequences emitted to open or close hyperlinks.
+    pub hyperlink_sequences: usize,
+
+    /// Number of bytes written (or queued in non-blocking output mode).
+    pub bytes_written: usize,
+
+    /// Time spent diffing, encoding and flushing.
+    pub elapsed: Duration,
+}
+
+impl std::ops::Add for RenderStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            frames: self.frames + other.frames,
+            cells_compared: self.cells_compared + other.cells_compared,
+            cells_changed: self.cells_changed + other.cells_changed,
+            cursor_moves: self.cursor_moves + other.cursor_moves,
+            sgr_sequences: self.sgr_sequences + other.sgr_sequences,
+            hyperlink_sequences: self.hyperlink_sequences + other.hyperlink_sequences,
+            bytes_written: self.bytes_written + other.bytes_written,
+            elapsed: self.elapsed + other.elapsed,
+        }
+    }
+}
+
+impl std::ops::AddAssign for RenderStats {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// Saves the window title and icon name on the terminal's stack, unless already saved.
+///
+/// They are restored by [`write_restore_sequences()`].
+fn push_title<W: Write>(mut writer: W) -> std::io::Result<()> {
+    if RESTORE_FLAGS.fetch_or(RESTORE_TITLE, Ordering::SeqCst) & RESTORE_TITLE == 0 {
+        // Save both the window title and the icon name
+        write!(writer, "\x1b[22;0t")?;
+    }
+    Ok(())
+}
+
+/// Sets the window title (`ps = 2`) or the icon name (`ps = 1`), saving the original ones first.
+fn write_title<W: Write>(mut writer: W, ps: u8, title: &str) -> std::io::Result<()> {
+    check_control_chars(title)?;
+    push_title(&mut writer)?;
+    write!(writer, "\x1b]{ps};{title}\x1b\\")
+}
+
+/// Sets the cursor color, or resets it to the default if `color` is `None`.
+fn write_cursor_color<W: Write>(
+    mut writer: W,
+    color: Option<TerminalColor>,
+) -> std::io::Result<()> {
+    if let Some(c) = color {
+        RESTORE_FLAGS.fetch_or(RESTORE_CURSOR_COLOR, Ordering::SeqCst);
+        write!(writer, "\x1b]12;{}\x1b\\", c.to_x11_rgb())
+    } else {
+        write!(writer, "\x1b]112\x1b\\")
+    }
+}
+
+/// Sets the pointer shape, or resets it to the default if `shape` is `None`.
+fn write_pointer_shape<W: Write>(mut writer: W, shape: Option<&str>) -> std::io::Result<()> {
+    let shape = shape.unwrap_or_default();
+    check_control_chars(shape)?;
+    if !shape.is_empty() {
+        RESTORE_FLAGS.fetch_or(RESTORE_POINTER_SHAPE, Ordering::SeqCst);
+    }
+    write!(writer, "\x1b]22;{shape}\x1b\\")
+}
+
+/// Writes the escape sequences that undo the settings recorded in [`RESTORE_FLAGS`].
+///
+/// The flags are cleared, so the sequences are written at most once
+/// even if both the panic hook and [`Drop`] run.
+fn write_restore_sequences<W: Write>(mut writer: W) -> std::io::Result<()> {
+    let flags = RESTORE_FLAGS.swap(0, Ordering::SeqCst);
+    if flags & RESTORE_CURSOR_STYLE != 0 {
+        write!(writer, "{}", CursorStyle::Default)?;
+    }
+    if flags & RESTORE_CURSOR_COLOR != 0 {
+        write!(writer, "\x1b]112\x1b\\")?;
+    }
+    if flags & RESTORE_POINTER_SHAPE != 0 {
+        write!(writer, "\x1b]22;\x1b\\")?;
+    }
+    if flags & RESTORE_FOREGROUND_COLOR != 0 {
+        write!(writer, "\x1b]110\x1b\\")?;
+    }
+    if flags & RESTORE_BACKGROUND_COLOR != 0 {
+        write!(writer, "\x1b]111\x1b\\")?;
+    }
+    if flags & RESTORE_PALETTE != 0 {
+        write!(writer, "\x1b]104\x1b\\")?;
+    }
+    if flags & RESTORE_APPLICATION_CURSOR != 0 {
+        write!(writer, "\x1b[?1l")?;
+    }
+    if flags & RESTORE_APPLICATION_KEYPAD != 0 {
+        write!(writer, "\x1b>")?;
+    }
+    if flags & RESTORE_MODIFY_OTHER_KEYS != 0 {
+        write!(writer, "\x1b[>4m")?;
+    }
+    if flags & RESTORE_TITLE != 0 {
+        // Pop the window title and icon name pushed by `push_title()`
+        write!(writer, "\x1b[23;0t")?;
+    }
+    Ok(())
+}
+
+/// Waits until `fd` becomes readable, returning `false` on timeout.
+///
+/// Interrupted waits (e.g., by SIGWINCH) are retried with the remaining time.
+fn wait_readable(fd: RawFd, timeout: Duration) -> std::io::Result<bool> {
+    let start_time = Instant::now();
+    let mut pollfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    loop {
+        let remaining = timeout.saturating_sub(start_time.elapsed());
+        let timeout_ms = remaining.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+        match unsafe { libc::poll(&mut pollfd, 1, timeout_ms) } {
+            -1 => {
+                let e = Error::last_os_error();
+                if e.kind() != ErrorKind::Interrupted {
+                    return Err(e);
+                }
+            }
+            0 => return Ok(false),
+            _ => return Ok(true),
+        }
+    }
+}
+
+fn check_control_chars(s: &str) -> std::io::Result<()> {
+    if s.chars().any(|c| c.is_control()) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "string must not contain control characters",
+        ));
+    }
+    Ok(())
+}
+
 fn check_libc_result(result: libc::c_int) -> std::io::Result<()> {
     if result == 0 {
         Ok(())
@@ -675,7 +2146,7 @@ fn set_sigwinch_handler() -> std::io::Result<File> {
 
         let mut sigaction = MaybeUninit::<libc::sigaction>::zeroed().assume_init();
 
-        sigaction.sa_sigaction = handle_sigwinch as libc::sighandler_t;
+        sigaction.sa_sigaction = handle_sigwinch as *const () as libc::sighandler_t;
         sigaction.sa_flags = 0;
 
         check_libc_result(libc::sigemptyset(&mut sigaction.sa_mask))?;
@@ -692,7 +2163,44 @@ fn set_sigwinch_handler() -> std::io::Result<File> {
 mod tests {
     use std::io::IsTerminal;
 
-    use super::Terminal;
+    use std::{
+        fmt::Write as _,
+        io::{ErrorKind, Read, Write as _},
+        os::unix::net::UnixStream,
+        sync::atomic::Ordering,
+        time::Duration,
+    };
+
+    use super::{
+        CoalescedEvent, RESTORE_APPLICATION_KEYPAD, RESTORE_CURSOR_COLOR, RESTORE_CURSOR_STYLE,
+        RESTORE_FLAGS, RESTORE_MODIFY_OTHER_KEYS, RESTORE_TITLE, RawModeConfig, RenderStats,
+        Renderer, Terminal, TerminalEvent, coalesce_events, push_title, raw_termios,
+        write_cursor_color, write_pointer_shape, write_restore_sequences, write_title,
+    };
+    use crate::{
+        CursorStyle, KeyCode, KeyInput, MouseButton, MouseEvent, MouseInput, TerminalColor,
+        TerminalFrame, TerminalHyperlink, TerminalInput, TerminalPosition, TerminalSize,
+        output::OutputWriter,
+    };
+
+    fn frame(text: &str) -> TerminalFrame {
+        let mut frame = TerminalFrame::new(TerminalSize::rows_cols(2, 8));
+        write!(frame, "{text}").expect("ok");
+        frame
+    }
+
+    /// Reads all bytes currently available from `peer`, which must be non-blocking.
+    fn read_available(peer: &mut UnixStream, received: &mut Vec<u8>) {
+        let mut buf = [0; 4096];
+        loop {
+            match peer.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => received.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => panic!("{e}"),
+            }
+        }
+    }
 
     #[test]
     fn duplicate_check() {
@@ -709,4 +2217,264 @@ mod tests {
         std::mem::drop(terminal);
         assert!(Terminal::new().is_ok());
     }
+
+    #[test]
+    fn coalesce() {
+        let mouse = |event, col| {
+            TerminalEvent::Input(TerminalInput::Mouse(MouseInput {
+                event,
+                position: TerminalPosition::row_col(0, col),
+                ctrl: false,
+                alt: false,
+                shift: false,
+                pixel_position: None,
+            }))
+        };
+        let key = TerminalEvent::Input(TerminalInput::Key(KeyInput {
+            ctrl: false,
+            alt: false,
+            code: KeyCode::Enter,
+        }));
+        let resize = |cols| TerminalEvent::Resize(TerminalSize::rows_cols(10, cols));
+        let drag = MouseEvent::Drag(MouseButton::Left);
+        let mut ctrl_scroll = mouse(MouseEvent::ScrollUp, 3);
+        if let TerminalEvent::Input(TerminalInput::Mouse(m)) = &mut ctrl_scroll {
+            m.ctrl = true;
+        }
+
+        let events = vec![
+            mouse(drag, 1),
+            mouse(drag, 2),
+            mouse(drag, 3),
+            mouse(MouseEvent::Move, 4),
+            mouse(MouseEvent::ScrollUp, 1),
+            mouse(MouseEvent::ScrollUp, 2),
+            ctrl_scroll,
+            mouse(MouseEvent::ScrollDown, 3),
+            key,
+            key,
+            resize(20),
+            resize(30),
+        ];
+        let merged = |event, count| CoalescedEvent { event, count };
+        assert_eq!(
+            coalesce_events(events),
+            [
+                merged(mouse(drag, 3), 3),
+                merged(mouse(MouseEvent::Move, 4), 1),
+                merged(mouse(MouseEvent::ScrollUp, 2), 2),
+                merged(ctrl_scroll, 1),
+                merged(mouse(MouseEvent::ScrollDown, 3), 1),
+                merged(key, 1),
+                merged(key, 1),
+                merged(resize(30), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_mode_flags() {
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        original.c_iflag = libc::BRKINT | libc::ICRNL | libc::IXON | libc::IXOFF;
+        original.c_oflag = libc::OPOST | libc::ONLCR;
+        original.c_cflag = libc::CS7 | libc::PARENB | libc::CREAD;
+        original.c_lflag = libc::ECHO | libc::ICANON | libc::IEXTEN | libc::ISIG;
+
+        let raw = raw_termios(original, RawModeConfig::new());
+        assert_eq!(raw.c_iflag, libc::IXOFF);
+        assert_eq!(raw.c_oflag, libc::ONLCR);
+        assert_eq!(raw.c_cflag, libc::CS8 | libc::CREAD);
+        assert_eq!(raw.c_lflag, 0);
+        assert_eq!(raw.c_cc[libc::VMIN], 1);
+        assert_eq!(raw.c_cc[libc::VTIME], 0);
+
+        let config = RawModeConfig::new()
+            .signals()
+            .output_processing()
+            .flow_control();
+        let raw = raw_termios(original, config);
+        assert_eq!(raw.c_iflag, libc::IXON | libc::IXOFF);
+        assert_eq!(raw.c_oflag, libc::OPOST | libc::ONLCR);
+        assert_eq!(raw.c_lflag, libc::ISIG);
+
+        // VTIME is rounded up to tenths of a second and capped at 25.5 seconds
+        let vtime = |timeout| {
+            let config = RawModeConfig::new().min_bytes(0).read_timeout(timeout);
+            let raw = raw_termios(original, config);
+            assert_eq!(raw.c_cc[libc::VMIN], 0);
+            raw.c_cc[libc::VTIME]
+        };
+        assert_eq!(vtime(Duration::ZERO), 0);
+        assert_eq!(vtime(Duration::from_millis(1)), 1);
+        assert_eq!(vtime(Duration::from_millis(100)), 1);
+        assert_eq!(vtime(Duration::from_millis(101)), 2);
+        assert_eq!(vtime(Duration::from_millis(25_500)), 255);
+        assert_eq!(vtime(Duration::from_secs(60)), 255);
+    }
+
+    #[test]
+    fn restore_sequences() {
+        RESTORE_FLAGS.store(0, Ordering::SeqCst);
+
+        // The title is pushed only once, however many times it is changed
+        let mut output = Vec::new();
+        push_title(&mut output).expect("ok");
+        push_title(&mut output).expect("ok");
+        assert_eq!(output, b"\x1b[22;0t");
+
+        RESTORE_FLAGS.fetch_or(
+            RESTORE_CURSOR_STYLE | RESTORE_APPLICATION_KEYPAD | RESTORE_MODIFY_OTHER_KEYS,
+            Ordering::SeqCst,
+        );
+        let mut output = Vec::new();
+        write_restore_sequences(&mut output).expect("ok");
+        assert_eq!(
+            String::from_utf8(output).expect("ok"),
+            format!("{}\x1b>\x1b[>4m\x1b[23;0t", CursorStyle::Default)
+        );
+
+        // The sequences are written at most once (e.g., by both the panic hook and `Drop`)
+        let mut output = Vec::new();
+        write_restore_sequences(&mut output).expect("ok");
+        assert!(output.is_empty());
+
+        // and the title is pushed again once restored
+        push_title(&mut output).expect("ok");
+        assert_eq!(output, b"\x1b[22;0t");
+        RESTORE_FLAGS.store(0, Ordering::SeqCst);
+
+        // Cursor colors and pointer shapes are reset if they have been set
+        let mut output = Vec::new();
+        write_cursor_color(&mut output, Some(TerminalColor::new(255, 0, 128))).expect("ok");
+        write_cursor_color(&mut output, None).expect("ok");
+        write_pointer_shape(&mut output, None).expect("ok");
+        assert_eq!(RESTORE_FLAGS.load(Ordering::SeqCst), RESTORE_CURSOR_COLOR);
+        write_pointer_shape(&mut output, Some("text")).expect("ok");
+        assert!(write_pointer_shape(&mut output, Some("\x1b\\")).is_err());
+        write_restore_sequences(&mut output).expect("ok");
+        assert_eq!(
+            String::from_utf8(output).expect("ok"),
+            "\x1b]12;rgb:ff/00/80\x1b\\\x1b]112\x1b\\\x1b]22;\x1b\\\x1b]22;text\x1b\\\
+             \x1b]112\x1b\\\x1b]22;\x1b\\"
+        );
+
+        // Titles are pushed before the first change and popped when restored
+        let mut output = Vec::new();
+        write_title(&mut output, 2, "one").expect("ok");
+        write_title(&mut output, 1, "icon").expect("ok");
+        write_title(&mut output, 2, "two").expect("ok");
+        assert!(write_title(&mut output, 2, "bad\x07").is_err());
+        assert_eq!(RESTORE_FLAGS.load(Ordering::SeqCst), RESTORE_TITLE);
+        write_restore_sequences(&mut output).expect("ok");
+        assert_eq!(
+            String::from_utf8(output).expect("ok"),
+            "\x1b[22;0t\x1b]2;one\x1b\\\x1b]1;icon\x1b\\\x1b]2;two\x1b\\\x1b[23;0t"
+        );
+    }
+
+    #[test]
+    fn render_stats() {
+        let (stream, mut peer) = UnixStream::pair().expect("ok");
+        peer.set_nonblocking(true).expect("ok");
+        let mut output = OutputWriter::new(stream);
+        let mut renderer = Renderer::default();
+        let mut received = Vec::new();
+
+        // The first frame is drawn entirely
+        renderer.draw(&mut output, frame("hello")).expect("ok");
+        read_available(&mut peer, &mut received);
+        let stats = renderer.last_render_stats;
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.cells_compared, 16);
+        assert_eq!(stats.cells_changed, 16);
+        assert_eq!(stats.cursor_moves, 2);
+        assert_eq!(stats.sgr_sequences, 1);
+        assert_eq!(stats.bytes_written, received.len());
+
+        // Only the differing cells are redrawn
+        received.clear();
+        renderer.cursor = Some(TerminalPosition::row_col(1, 0));
+        renderer.draw(&mut output, frame("help\n!")).expect("ok");
+        read_available(&mut peer, &mut received);
+        let stats = renderer.last_render_stats;
+        assert_eq!(stats.cells_compared, 16);
+        assert_eq!(stats.cells_changed, 3);
+        assert_eq!(stats.cursor_moves, 3);
+        assert_eq!(stats.sgr_sequences, 1);
+        assert_eq!(stats.bytes_written, received.len());
+        assert_eq!(
+            String::from_utf8_lossy(&received),
+            "\x1b[?25l\x1b[1;4H\x1b[0mp \x1b[2;1H!\x1b[2;1H\x1b[?25h"
+        );
+
+        let total = renderer.total_render_stats;
+        assert_eq!(
+            total,
+            RenderStats {
+                frames: 2,
+                cells_compared: 32,
+                cells_changed: 19,
+                cursor_moves: 5,
+                sgr_sequences: 2,
+                hyperlink_sequences: 0,
+                bytes_written: output.total_bytes() as usize,
+                elapsed: total.elapsed,
+            }
+        );
+
+        // Hyperlinks are opened and closed around the linked cells, and the open link is
+        // closed at the end
+        received.clear();
+        let link = TerminalHyperlink::new("https://a.b").expect("ok");
+        let end = TerminalHyperlink::END;
+        renderer.cursor = None;
+        renderer
+            .draw(&mut output, frame(&format!("{link}he{end}Lp\n!{link}?")))
+            .expect("ok");
+        read_available(&mut peer, &mut received);
+        let stats = renderer.last_render_stats;
+        assert_eq!(stats.cells_changed, 4);
+        assert_eq!(stats.hyperlink_sequences, 4);
+        assert_eq!(stats.bytes_written, received.len());
+        assert_eq!(
+            String::from_utf8(received).expect("ok"),
+            "\x1b[?25l\x1b[1;1H\x1b[0m\x1b]8;;https://a.b\x1b\\he\x1b]8;;\x1b\\L\x1b[2;2H\
+             \x1b]8;;https://a.b\x1b\\?\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn deferred_frames() {
+        let (stream, mut peer) = UnixStream::pair().expect("ok");
+        peer.set_nonblocking(true).expect("ok");
+        let mut output = OutputWriter::new(stream);
+        output.set_nonblocking(true).expect("ok");
+        let mut renderer = Renderer::default();
+
+        // Fill the socket buffer so that the output stays pending
+        output.write_all(&vec![b'.'; 1024 * 1024]).expect("ok");
+        renderer.flush(&mut output).expect("ok");
+        assert!(renderer.has_pending_output(&output));
+
+        // Frames drawn in the meantime are coalesced into the latest one
+        renderer.draw(&mut output, frame("first")).expect("ok");
+        renderer
+            .draw_ref(&mut output, &frame("second"))
+            .expect("ok");
+        let mut third = frame("third");
+        renderer.draw_and_swap(&mut output, &mut third).expect("ok");
+        assert_eq!(renderer.total_render_stats.frames, 0);
+
+        let mut received = Vec::new();
+        while !renderer.flush(&mut output).expect("ok") {
+            read_available(&mut peer, &mut received);
+        }
+        read_available(&mut peer, &mut received);
+        assert_eq!(received.len() as u64, output.total_bytes());
+        assert_eq!(renderer.total_render_stats.frames, 1);
+
+        let rendered = String::from_utf8_lossy(&received);
+        assert!(rendered.ends_with(".\x1b[?25l\x1b[1;1H\x1b[0mthird   \x1b[2;1H        "));
+        assert!(!rendered.contains("first") && !rendered.contains("second"));
+    }
 }
@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+
+/// Index of the `kf1` (F1 key) string capability.
+pub const KEY_F1: usize = 66;
+
+/// Index of the `kDC` (Shift+Delete key) string capability.
+pub const KEY_SDC: usize = 191;
+
+/// Index of the `kHOM` (Shift+Home key) string capability.
+pub const KEY_SHOME: usize = 199;
+
+const MAGIC: i16 = 0o432;
+const MAGIC_32BIT: i16 = 0o1036;
+
+/// Reads the compiled terminfo entry of `term` from the standard locations.
+///
+/// Both the `x/xterm` and the `78/xterm` (hexadecimal) directory layouts are searched.
+pub fn load(term: &str) -> Option<Vec<u8>> {
+    let first = term.chars().next()?;
+    if term.contains('/') || term.starts_with('.') {
+        return None;
+    }
+
+    let mut dirs = Vec::new();
+    if let Some(dir) = std::env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Ok(list) = std::env::var("TERMINFO_DIRS") {
+        // An empty element stands for the system default
+        dirs.extend(list.split(':').map(|dir| match dir {
+            "" => PathBuf::from("/usr/share/terminfo"),
+            dir => PathBuf::from(dir),
+        }));
+    }
+    dirs.extend(["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo"].map(PathBuf::from));
+
+    dirs.iter().find_map(|dir| {
+        [first.to_string(), format!("{:x}", u32::from(first))]
+            .iter()
+            .find_map(|subdir| std::fs::read(dir.join(subdir).join(term)).ok())
+    })
+}
+
+/// Returns the string capabilities of a compiled terminfo entry, indexed as in `term.h`.
+///
+/// Absent and cancelled capabilities are `None`. Returns `None` if `entry` is malformed.
+pub fn string_capabilities(entry: &[u8]) -> Option<Vec<Option<&[u8]>>> {
+    let short = |offset: usize| -> Option<i16> {
+        let bytes = entry.get(offset..offset + 2)?;
+        Some(i16::from_le_bytes([bytes[0], bytes[1]]))
+    };
+    let count = |i: usize| short(i * 2).and_then(|n| usize::try_from(n).ok());
+    let number_size = match short(0)? {
+        MAGIC => 2,
+        MAGIC_32BIT => 4,
+        _ => return None,
+    };
+    let names_size = count(1)?;
+    let bools_count = count(2)?;
+    let nums_count = count(3)?;
+    let strings_count = count(4)?;
+    let table_size = count(5)?;
+
+    // The numbers section is aligned to an even offset
+    let bools_end = 12 + names_size + bools_count;
+    let offsets_start = bools_end + bools_end % 2 + nums_count * number_size;
+    let table_start = offsets_start + strings_count * 2;
+    let table = entry.get(table_start..table_start + table_size)?;
+    (0..strings_count)
+        .map(|i| {
+            // Absent and cancelled capabilities have negative offsets
+            let Ok(offset) = usize::try_from(short(offsets_start + i * 2)?) else {
+                return Some(None);
+            };
+            let value = table.get(offset..)?;
+            let len = value.iter().position(|&b| b == 0)?;
+            Some(Some(&value[..len]))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InputDialect;
+
+    /// Builds a compiled terminfo entry with the given string capabilities.
+    fn entry(magic: i16, strings: &[(usize, &[u8])]) -> Vec<u8> {
+        let strings_count = strings.iter().map(|(i, _)| i + 1).max().unwrap_or(0);
+        let mut offsets = vec![-1i16; strings_count];
+        let mut table = Vec::new();
+        for (i, value) in strings {
+            offsets[*i] = table.len() as i16;
+            table.extend_from_slice(value);
+            table.push(0);
+        }
+
+        let names = b"test|test terminal\0";
+        let header = [magic, names.len() as i16, 2, 1, strings_count as i16];
+        let mut entry = Vec::new();
+        for n in header.into_iter().chain([table.len() as i16]) {
+            entry.extend_from_slice(&n.to_le_bytes());
+        }
+        entry.extend_from_slice(names);
+        entry.extend_from_slice(&[1, 0]);
+        entry.resize(entry.len() + entry.len() % 2, 0);
+        entry.resize(entry.len() + if magic == MAGIC { 2 } else { 4 }, 0xff);
+        for offset in offsets {
+            entry.extend_from_slice(&offset.to_le_bytes());
+        }
+        entry.extend_from_slice(&table);
+        entry
+    }
+
+    #[test]
+    fn parse_string_capabilities() {
+        for magic in [MAGIC, MAGIC_32BIT] {
+            let entry = entry(magic, &[(KEY_F1, b"\x1bOP"), (KEY_SHOME, b"\x1b[7$")]);
+            let strings = string_capabilities(&entry).expect("valid entry");
+            assert_eq!(strings.len(), KEY_SHOME + 1);
+            assert_eq!(strings[KEY_F1], Some(&b"\x1bOP"[..]));
+            assert_eq!(strings[KEY_SHOME], Some(&b"\x1b[7$"[..]));
+            assert_eq!(strings[KEY_SDC], None);
+            assert_eq!(string_capabilities(&entry[..entry.len() - 3]), None);
+        }
+        assert_eq!(string_capabilities(b"\x1a\x01"), None);
+        assert_eq!(string_capabilities(&entry(0o123, &[(0, b"x")])), None);
+    }
+
+    #[test]
+    fn input_dialects() {
+        let dialect =
+            |strings: &[(usize, &[u8])]| InputDialect::from_terminfo_entry(&entry(MAGIC, strings));
+        assert_eq!(dialect(&[(KEY_F1, b"\x1b[[A")]), Some(InputDialect::Linux));
+        assert_eq!(
+            dialect(&[
+                (KEY_F1, b"\x1b[11~"),
+                (KEY_SDC, b"\x1b[3$"),
+                (KEY_SHOME, b"\x1b[7$")
+            ]),
+            Some(InputDialect::Rxvt)
+        );
+        assert_eq!(
+            dialect(&[
+                (KEY_F1, b"\x1bOP"),
+                (KEY_SDC, b"\x1b[3;2~"),
+                (KEY_SHOME, b"\x1b[1;2H")
+            ]),
+            Some(InputDialect::Xterm)
+        );
+        assert_eq!(dialect(&[]), Some(InputDialect::Xterm));
+        assert_eq!(InputDialect::from_terminfo_entry(b"\x1a"), None);
+    }
+}